@@ -23,14 +23,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("1 argument is expected: event url");
 
     let event = datumprikker::download_event(url.as_str()).await?;
-    println!("event url: {}", event.canonical_url);
-    println!("title: {}", event.title);
-    if let Some(final_date) = event.final_date {
-        println!("start: {}", final_date.start.with_timezone(&chrono::Local));
-        println!("end: {}", final_date.end.with_timezone(&chrono::Local));
-    } else {
-        println!("no final date selected")
-    }
+    println!("{}", event.display_local());
 
     Ok(())
 }
@@ -0,0 +1,174 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use crate::event::{AdminEvent, Invitee};
+use crate::event_overview_page::ParsePageError;
+use crate::event_url::EventUrl;
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err)
+)]
+pub fn parse_page(text: &str) -> Result<AdminEvent, ParsePageError> {
+    let document = select::document::Document::from(text);
+
+    let event = crate::event_overview_page::parse_document(&document)?;
+    if !is_organizer_view(&document)? {
+        return Err(ParsePageError::NotOrganizerView);
+    }
+
+    Ok(AdminEvent {
+        invitees: parse_page_invitees(&document)?,
+        pick_date_link: parse_page_pick_date_link(&document, &event.canonical_url)?,
+        event,
+    })
+}
+
+fn is_organizer_view(document: &select::document::Document) -> Result<bool, ParsePageError> {
+    Ok(document
+        .find(select::predicate::Name("article"))
+        .next()
+        .ok_or(ParsePageError::UnexpectedHtml)?
+        .is(select::predicate::Class("organizer")))
+}
+
+fn parse_page_invitees(
+    document: &select::document::Document,
+) -> Result<Vec<Invitee>, ParsePageError> {
+    document
+        .find(select::predicate::Descendant(
+            select::predicate::Attr("id", "participants"),
+            select::predicate::Class("item"),
+        ))
+        .map(|item| {
+            let email = item.attr("title").unwrap_or_default();
+            Ok(Invitee {
+                name: item
+                    .find(select::predicate::Class("name"))
+                    .next()
+                    .ok_or(ParsePageError::UnexpectedHtml)?
+                    .text()
+                    .trim()
+                    .to_string(),
+                email: if email.is_empty() {
+                    None
+                } else {
+                    Some(email.to_string())
+                },
+                responded: item.is(select::predicate::Class("responded")),
+            })
+        })
+        .collect()
+}
+
+fn parse_page_pick_date_link(
+    document: &select::document::Document,
+    canonical_url: &EventUrl,
+) -> Result<EventUrl, ParsePageError> {
+    let href = document
+        .find(select::predicate::Name("a"))
+        .filter_map(|link| link.attr("href"))
+        .find(|href| href.starts_with("/afspraak/selecteer/"))
+        .ok_or(ParsePageError::UnexpectedHtml)?;
+    let url = canonical_url
+        .url()
+        .join(href)
+        .map_err(|_err| ParsePageError::UnexpectedHtml)?;
+    EventUrl::try_from(url).map_err(|_err| ParsePageError::UnexpectedHtml)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::admin_page::parse_page;
+    use crate::event::{Contact, Invitee};
+    use crate::event_overview_page::ParsePageError;
+
+    #[test]
+    fn in_progress_event() {
+        let text = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let event = parse_page(text).unwrap();
+        assert_eq!(
+            event.invitees,
+            vec![Invitee {
+                name: String::from("Casper"),
+                email: Some(String::from("casper@meijn.net")),
+                responded: true,
+            }]
+        );
+        assert_eq!(
+            event.pick_date_link,
+            "http://datumprikker.nl/afspraak/selecteer/fewqvuycnmvgnx25"
+        );
+        assert_eq!(
+            event.contacts(),
+            vec![Contact {
+                name: String::from("Casper"),
+                email: Some(String::from("casper@meijn.net")),
+            }]
+        );
+    }
+
+    #[test]
+    fn finalized_event() {
+        let text = include_str!("../data/afspraak_overzicht_finalized.html");
+        let event = parse_page(text).unwrap();
+        assert_eq!(
+            event.invitees,
+            vec![
+                Invitee {
+                    name: String::from("Casper"),
+                    email: Some(String::from("casper@meijn.net")),
+                    responded: true,
+                },
+                Invitee {
+                    name: String::from("Jeroen"),
+                    email: Some(String::from("jeroengruijs@hotmail.com")),
+                    responded: true,
+                },
+                Invitee {
+                    name: String::from("Mark"),
+                    email: Some(String::from("mark.bruin@hotmail.com")),
+                    responded: true,
+                },
+                Invitee {
+                    name: String::from("Robin"),
+                    email: Some(String::from("de.gier.robin@gmail.com")),
+                    responded: true,
+                },
+            ]
+        );
+        assert_eq!(
+            event.pick_date_link,
+            "http://datumprikker.nl/afspraak/selecteer/f4wfumjp7a9ih2nq"
+        );
+        assert_eq!(event.pending_invitees().count(), 0);
+    }
+
+    #[test]
+    fn participant_event() {
+        let text = include_str!("../data/afspraak_overzicht_participant.html");
+        let event = parse_page(text);
+        assert_eq!(event, Err(ParsePageError::NotOrganizerView))
+    }
+
+    #[test]
+    fn invalid_event() {
+        let text = include_str!("../data/afspraak_overzicht_invalid.html");
+        let event = parse_page(text);
+        assert_eq!(event, Err(ParsePageError::NonExistingEvent))
+    }
+}
@@ -0,0 +1,208 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+//! Blocking variant of the download API, for applications without an async runtime
+
+use crate::download::{parse_download_url, DownloadError};
+use crate::event::{AdminEvent, Contact, Event};
+use std::io::Read;
+
+/// Blocking counterpart of [`crate::Client`]
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::blocking::Client,
+    base_url: url::Url,
+    max_body_size: usize,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a preconfigured HTTP client, e.g. with default headers or custom TLS settings
+    pub fn with_http_client(http: reqwest::blocking::Client) -> Self {
+        Client {
+            http,
+            base_url: crate::event::default_base_url(),
+            max_body_size: crate::client::DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// Location of the site, which defaults to `https://datumprikker.nl/`; used to look up events
+    /// by code and to recognize event URLs, e.g. to point the client at a test server
+    pub fn base_url(mut self, base_url: url::Url) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// Largest page in bytes that is downloaded, which defaults to 10 MiB
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    pub fn download_event(&self, url: impl AsRef<str>) -> Result<Event, DownloadError> {
+        let url = parse_download_url(url.as_ref())?;
+        let resp = self.download_page(url.as_str())?;
+        let event = crate::event_overview_page::parse_page(resp.as_str())
+            .map_err(DownloadError::parse(&url))?;
+        Ok(event)
+    }
+
+    /// Downloads the event with the given code, e.g. `fewqvuycnmvgnx25`
+    pub fn download_event_by_code(&self, code: &str) -> Result<Event, DownloadError> {
+        let url = crate::event::event_url_at(&self.base_url, code)
+            .ok_or(DownloadError::InvalidEventUrl)?;
+        self.download_event(url.as_str())
+    }
+
+    /// Downloads the event using the organizer's link, which shows the invitees as well
+    pub fn download_event_admin(&self, url: impl AsRef<str>) -> Result<AdminEvent, DownloadError> {
+        let url = parse_download_url(url.as_ref())?;
+        let resp = self.download_page(url.as_str())?;
+        let event =
+            crate::admin_page::parse_page(resp.as_str()).map_err(DownloadError::parse(&url))?;
+        Ok(event)
+    }
+
+    /// Downloads the contact details of the participants using the organizer's link
+    pub fn export_participants(&self, url: impl AsRef<str>) -> Result<Vec<Contact>, DownloadError> {
+        Ok(self.download_event_admin(url)?.contacts())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err))]
+    pub(crate) fn download_page(&self, url: &str) -> Result<String, DownloadError> {
+        let resp = self
+            .http
+            .get(url)
+            .send()
+            .map_err(DownloadError::network(url))?;
+        let location = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok());
+        if crate::client::is_redirect_to_home(
+            &self.base_url,
+            url,
+            resp.url(),
+            resp.status().as_u16(),
+            location,
+        ) {
+            return Err(DownloadError::parse(resp.url())(
+                crate::ParsePageError::NonExistingEvent,
+            ));
+        }
+        if !resp.status().is_success() {
+            let retry_after = resp
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok());
+            return Err(crate::download::status_error(
+                resp.url(),
+                resp.status().as_u16(),
+                retry_after,
+            ));
+        }
+        let final_url = resp.url().clone();
+        let limit = self.max_body_size;
+        let mut body = Vec::new();
+        resp.take(limit as u64 + 1)
+            .read_to_end(&mut body)
+            .map_err(|err| DownloadError::TransportError(Box::new(err)))?;
+        if body.len() > limit {
+            return Err(DownloadError::ResponseTooLarge { url: final_url });
+        }
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::with_http_client(
+            default_http_client().expect("failed to initialize the HTTP client"),
+        )
+    }
+}
+
+fn default_http_client() -> Result<reqwest::blocking::Client, reqwest::Error> {
+    reqwest::blocking::Client::builder()
+        .user_agent(crate::client::DEFAULT_USER_AGENT)
+        .build()
+}
+
+/// Client shared by the free functions, so that sequential downloads reuse connections
+///
+/// A client that fails to initialize is not stored, so the next call tries again.
+fn default_client() -> Result<&'static Client, DownloadError> {
+    static CLIENT: std::sync::OnceLock<Client> = std::sync::OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let http = default_http_client().map_err(DownloadError::ClientError)?;
+    Ok(CLIENT.get_or_init(|| Client::with_http_client(http)))
+}
+
+pub fn download_event(url: impl AsRef<str>) -> Result<Event, DownloadError> {
+    default_client()?.download_event(url)
+}
+
+/// Downloads the event with the given code, e.g. `fewqvuycnmvgnx25`
+pub fn download_event_by_code(code: &str) -> Result<Event, DownloadError> {
+    default_client()?.download_event_by_code(code)
+}
+
+/// Downloads the event using the organizer's link, which shows the invitees as well
+pub fn download_event_admin(url: impl AsRef<str>) -> Result<AdminEvent, DownloadError> {
+    default_client()?.download_event_admin(url)
+}
+
+/// Downloads the contact details of the participants using the organizer's link
+pub fn export_participants(url: impl AsRef<str>) -> Result<Vec<Contact>, DownloadError> {
+    default_client()?.export_participants(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::Client;
+    use crate::download::DownloadError;
+    use crate::test_server::{response, serve};
+
+    #[test]
+    fn download_from_base_url() {
+        let page = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let (base, server) = serve(vec![response("200 OK", &[], page)]);
+        let client = Client::new().base_url(base);
+        let event = client.download_event_by_code("fewqvuycnmvgnx25").unwrap();
+        assert_eq!(event.title, "D&D Avernus week 29");
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /afspraak/overzicht/fewqvuycnmvgnx25 "));
+    }
+
+    #[test]
+    fn response_too_large() {
+        let page = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let (base, server) = serve(vec![response("200 OK", &[], page)]);
+        let client = Client::new().max_body_size(1024);
+        let url = base.join("afspraak/overzicht/fewqvuycnmvgnx25").unwrap();
+        let result = client.download_event(url);
+        assert!(matches!(
+            result,
+            Err(DownloadError::ResponseTooLarge { .. })
+        ));
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Downloaded page together with the validators needed for a conditional request
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedPage {
+    pub body: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Storage for downloaded pages, so unchanged pages don't have to be transferred again
+///
+/// Caching is best effort: a store that fails to read or write simply acts as a cache miss. The
+/// client calls the store from a blocking thread, so an implementation may do blocking I/O.
+pub trait CacheStore: std::fmt::Debug + Send + Sync {
+    fn get(&self, url: &str) -> Option<CachedPage>;
+    fn put(&self, url: &str, page: CachedPage);
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    pages: Mutex<HashMap<String, CachedPage>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, url: &str) -> Option<CachedPage> {
+        self.pages.lock().ok()?.get(url).cloned()
+    }
+
+    fn put(&self, url: &str, page: CachedPage) {
+        if let Ok(mut pages) = self.pages.lock() {
+            pages.insert(url.to_string(), page);
+        }
+    }
+}
+
+/// Cache that stores every page as a file in a directory
+///
+/// Files are named after a hash of the URL, so long URLs don't exceed the file name limit. The
+/// URL is stored in the file as well, so a hash collision is a cache miss instead of a wrong page.
+#[derive(Debug)]
+pub struct DiskCache {
+    directory: PathBuf,
+}
+
+impl DiskCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        DiskCache {
+            directory: directory.into(),
+        }
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.directory.join(format!("{:016x}", fnv1a(url)))
+    }
+}
+
+/// 64-bit FNV-1a hash, which unlike the standard library hasher is stable between releases
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, url: &str) -> Option<CachedPage> {
+        let text = std::fs::read_to_string(self.path(url)).ok()?;
+        let (headers, body) = text.split_once("\n\n")?;
+        let (stored_url, headers) = headers.split_once('\n').unwrap_or((headers, ""));
+        if stored_url != url {
+            return None;
+        }
+        let mut page = CachedPage {
+            body: body.to_string(),
+            etag: None,
+            last_modified: None,
+        };
+        for line in headers.lines() {
+            match line.split_once(": ")? {
+                ("etag", value) => page.etag = Some(value.to_string()),
+                ("last-modified", value) => page.last_modified = Some(value.to_string()),
+                _ => return None,
+            }
+        }
+        Some(page)
+    }
+
+    fn put(&self, url: &str, page: CachedPage) {
+        let mut text = format!("{}\n", url);
+        if let Some(etag) = page.etag {
+            text += &format!("etag: {}\n", etag);
+        }
+        if let Some(last_modified) = page.last_modified {
+            text += &format!("last-modified: {}\n", last_modified);
+        }
+        text += "\n";
+        text += &page.body;
+        // Every write has a temporary file of its own, as the same URL may be stored concurrently
+        static WRITES: AtomicUsize = AtomicUsize::new(0);
+        let path = self.path(url);
+        let temporary = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::create_dir_all(&self.directory)
+            .and_then(|()| std::fs::write(&temporary, text))
+            .and_then(|()| std::fs::rename(&temporary, path));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::{CacheStore, CachedPage, DiskCache, MemoryCache};
+
+    fn page() -> CachedPage {
+        CachedPage {
+            body: String::from("<html>\n\n</html>"),
+            etag: Some(String::from("\"abc\"")),
+            last_modified: Some(String::from("Wed, 21 Oct 2015 07:28:00 GMT")),
+        }
+    }
+
+    #[test]
+    fn memory_cache() {
+        let cache = MemoryCache::new();
+        let url = "https://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25";
+        assert_eq!(cache.get(url), None);
+        cache.put(url, page());
+        assert_eq!(cache.get(url), Some(page()));
+    }
+
+    #[test]
+    fn disk_cache() {
+        let directory = std::env::temp_dir().join(format!("datumprikker-{}", std::process::id()));
+        let cache = DiskCache::new(&directory);
+        let url = "https://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25";
+        assert_eq!(cache.get(url), None);
+        cache.put(url, page());
+        assert_eq!(cache.get(url), Some(page()));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn disk_cache_long_url() {
+        let directory =
+            std::env::temp_dir().join(format!("datumprikker-long-{}", std::process::id()));
+        let cache = DiskCache::new(&directory);
+        let url = format!(
+            "https://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25?{}",
+            "utm_source=x&".repeat(40)
+        );
+        cache.put(&url, page());
+        assert_eq!(cache.get(&url), Some(page()));
+        assert_eq!(cache.get("https://datumprikker.nl/"), None);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
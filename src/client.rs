@@ -0,0 +1,959 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use crate::cache::{CacheStore, CachedPage};
+use crate::download::DownloadError;
+use crate::event::{AdminEvent, Contact, Event};
+use crate::event_overview_page::ParsePageError;
+use crate::middleware::Middleware;
+use crate::observer::{RequestInfo, RequestObserver};
+use crate::rate_limit::RateLimiter;
+use crate::raw_page::RawPage;
+use crate::retry::RetryPolicy;
+use futures_util::StreamExt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Largest page that is accepted, which is far beyond the size of any real event page
+pub(crate) const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+pub(crate) const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Client for downloading events, which shares its connection pool between downloads
+///
+/// # Cancellation
+///
+/// All futures returned by the client are cancel safe: dropping one, e.g. from a
+/// `tokio::select!` branch or with `tokio::time::timeout`, aborts the request in flight. A page is
+/// only stored in the cache once it is completely received, so a cancelled download never leaves
+/// a partial page behind. Dropping the future of [`Client::download_events`] cancels all of its
+/// downloads.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    retry_policy: RetryPolicy,
+    timeout: Option<Duration>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    cache: Option<Arc<dyn CacheStore>>,
+    max_body_size: usize,
+    observer: Option<Arc<dyn RequestObserver>>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    base_url: url::Url,
+    accept_language: Option<String>,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Uses a preconfigured HTTP client, e.g. with default headers or custom TLS settings
+    pub fn with_http_client(http: reqwest::Client) -> Self {
+        Client {
+            http,
+            retry_policy: RetryPolicy::default(),
+            timeout: None,
+            rate_limiter: None,
+            cache: None,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            observer: None,
+            middleware: Vec::new(),
+            base_url: url::Url::parse(crate::event::DEFAULT_BASE_URL)
+                .expect("default base URL is valid"),
+            accept_language: None,
+        }
+    }
+
+    pub async fn download_event(&self, url: impl AsRef<str>) -> Result<Event, DownloadError> {
+        self.download_and_parse(url.as_ref(), RawPage::parse).await
+    }
+
+    /// Returns the canonical overview URL of the event, also for a short link
+    ///
+    /// A short link has a code of its own, so it is downloaded to follow its redirect or, if it
+    /// isn't redirected, to read the canonical link of the page.
+    pub async fn resolve_event_url(&self, url: impl AsRef<str>) -> Result<url::Url, DownloadError> {
+        let url = url.as_ref();
+        if let Some(url) = crate::event::normalize_event_url_at(&self.base_url, url) {
+            return Ok(url);
+        }
+        let page = self.fetch_page(url).await?;
+        if let Some(url) =
+            crate::event::normalize_event_url_at(&self.base_url, page.final_url.as_str())
+        {
+            return Ok(url);
+        }
+        let canonical_url = page
+            .parse()
+            .map_err(DownloadError::parse(&page.final_url))?
+            .canonical_url;
+        Ok(
+            crate::event::normalize_event_url_at(&self.base_url, canonical_url.as_str())
+                .unwrap_or_else(|| canonical_url.url().clone()),
+        )
+    }
+
+    /// Downloads several events at once, with at most `max_concurrent` downloads in flight
+    ///
+    /// The results are in the same order as the URLs.
+    pub async fn download_events<I>(
+        &self,
+        urls: I,
+        max_concurrent: usize,
+    ) -> Vec<Result<Event, DownloadError>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        futures_util::stream::iter(urls)
+            .map(|url| async move { self.download_event(url.as_ref()).await })
+            .buffered(max_concurrent.max(1))
+            .collect()
+            .await
+    }
+
+    /// Downloads the event with the given code, e.g. `fewqvuycnmvgnx25`
+    pub async fn download_event_by_code(&self, code: &str) -> Result<Event, DownloadError> {
+        let url = crate::event::event_url_at(&self.base_url, code)
+            .ok_or(DownloadError::InvalidEventUrl)?;
+        self.download_event(url.as_str()).await
+    }
+
+    /// Downloads the event using the organizer's link, which shows the invitees as well
+    pub async fn download_event_admin(
+        &self,
+        url: impl AsRef<str>,
+    ) -> Result<AdminEvent, DownloadError> {
+        self.download_and_parse(url.as_ref(), RawPage::parse_admin)
+            .await
+    }
+
+    /// Downloads the contact details of the participants using the organizer's link
+    pub async fn export_participants(
+        &self,
+        url: impl AsRef<str>,
+    ) -> Result<Vec<Contact>, DownloadError> {
+        Ok(self.download_event_admin(url).await?.contacts())
+    }
+
+    async fn download_and_parse<T>(
+        &self,
+        url: &str,
+        parse: impl Fn(&RawPage) -> Result<T, ParsePageError>,
+    ) -> Result<T, DownloadError> {
+        let mut attempt = 1;
+        loop {
+            // The cache stores a page before it is parsed, so revalidating it after a parse
+            // error would hand back the same broken page
+            let revalidate = attempt == 1;
+            // Network retries and parse retries share one budget of attempts
+            let page = self.download_page(url, revalidate, &mut attempt).await?;
+            match parse(&page) {
+                Err(error) if self.retry_policy.should_retry_parse(attempt, &error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(attempt, %error, "downloading again after parse error");
+                    tokio::time::sleep(self.retry_policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result.map_err(DownloadError::parse(&page.final_url)),
+            }
+        }
+    }
+
+    /// Downloads the page without parsing it, e.g. to archive the HTML next to the event
+    pub async fn fetch_page(&self, url: impl AsRef<str>) -> Result<RawPage, DownloadError> {
+        self.download_page(url.as_ref(), true, &mut 1).await
+    }
+
+    /// Downloads the page, conditionally when `revalidate` is set and the page is cached
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, attempt), err))]
+    async fn download_page(
+        &self,
+        url: &str,
+        revalidate: bool,
+        attempt: &mut u32,
+    ) -> Result<RawPage, DownloadError> {
+        self.with_retries(attempt, move || async move {
+            let mut info = RequestInfo::new(url);
+            let result = self.try_download_page(url, revalidate, &mut info).await;
+            (result, info)
+        })
+        .await
+    }
+
+    /// Checks cheaply whether the page changed since the previous snapshot was fetched
+    ///
+    /// Only the headers are requested, conditionally when the snapshot has an ETag or
+    /// Last-Modified header. Without those validators, a page with the same length is assumed to
+    /// be unchanged. When it can't be decided, the page is reported as changed, so a full download
+    /// is never skipped wrongly.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(url = url.as_ref()), err)
+    )]
+    pub async fn has_changed(
+        &self,
+        url: impl AsRef<str>,
+        previous: &RawPage,
+    ) -> Result<bool, DownloadError> {
+        let url = url.as_ref();
+        self.with_retries(&mut 1, move || async move {
+            let mut info = RequestInfo::new(url);
+            let result = self.try_has_changed(url, previous, &mut info).await;
+            (result, info)
+        })
+        .await
+    }
+
+    /// Runs attempts until one succeeds or the retry policy gives up, reporting each of them to
+    /// the observer
+    ///
+    /// `attempt` is the number of the current attempt, which is advanced for every retry.
+    async fn with_retries<T, F, Fut>(
+        &self,
+        attempt: &mut u32,
+        mut attempt_once: F,
+    ) -> Result<T, DownloadError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = (Result<T, DownloadError>, RequestInfo)>,
+    {
+        loop {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            let start = Instant::now();
+            let (result, mut info) = attempt_once().await;
+            if let Some(observer) = &self.observer {
+                info.duration = start.elapsed();
+                observer.on_request(&info);
+            }
+            match result {
+                Err(DownloadError::NetworkError { source, .. })
+                    if self.retry_policy.should_retry(*attempt, &source) =>
+                {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(attempt, error = %source, "retrying after network error");
+                    tokio::time::sleep(self.retry_policy.delay(*attempt)).await;
+                    *attempt += 1;
+                }
+                Err(DownloadError::RateLimited {
+                    retry_after: Some(retry_after),
+                    ..
+                }) if self
+                    .retry_policy
+                    .should_wait_for_rate_limit(*attempt, retry_after) =>
+                {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(attempt, ?retry_after, "retrying after being rate limited");
+                    tokio::time::sleep(retry_after).await;
+                    *attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Sends the request through the middleware and turns error statuses into errors
+    ///
+    /// A 304 Not Modified response is returned as is, so the caller can use its cached copy.
+    async fn send(
+        &self,
+        url: &str,
+        mut request: reqwest::RequestBuilder,
+        info: &mut RequestInfo,
+    ) -> Result<reqwest::Response, DownloadError> {
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        if let Some(accept_language) = &self.accept_language {
+            request = request.header(reqwest::header::ACCEPT_LANGUAGE, accept_language);
+        }
+        for middleware in &self.middleware {
+            request = middleware.on_request(request);
+        }
+        let resp = request.send().await.map_err(DownloadError::network(url))?;
+        for middleware in &self.middleware {
+            middleware.on_response(&resp);
+        }
+        #[cfg(feature = "tracing")]
+        {
+            if resp.url().as_str() != url {
+                tracing::debug!(final_url = %resp.url(), "followed redirect");
+            }
+            tracing::debug!(status = resp.status().as_u16(), "received response");
+        }
+        info.status = Some(resp.status().as_u16());
+        let location = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok());
+        let status = resp.status().as_u16();
+        if is_redirect_to_home(&self.base_url, url, resp.url(), status, location) {
+            return Err(DownloadError::parse(resp.url())(
+                ParsePageError::NonExistingEvent,
+            ));
+        }
+        if resp.status().is_success() || resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(resp);
+        }
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok());
+        Err(crate::download::status_error(
+            resp.url(),
+            resp.status().as_u16(),
+            retry_after,
+        ))
+    }
+
+    async fn try_has_changed(
+        &self,
+        url: &str,
+        previous: &RawPage,
+        info: &mut RequestInfo,
+    ) -> Result<bool, DownloadError> {
+        let mut request = self.http.head(url);
+        if let Some(etag) = previous.header("etag") {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = previous.header("last-modified") {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        let resp = self.send(url, request, info).await?;
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(false);
+        }
+        Ok(changed_since(previous, resp.headers()))
+    }
+
+    /// Looks up the page in the cache on a blocking thread, as the store may do file I/O
+    async fn cache_get(&self, url: &str) -> Option<CachedPage> {
+        let cache = self.cache.clone()?;
+        let url = url.to_string();
+        tokio::task::spawn_blocking(move || cache.get(&url))
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Stores the page in the cache on a blocking thread, as the store may do file I/O
+    async fn cache_put(&self, url: &str, page: CachedPage) {
+        if let Some(cache) = self.cache.clone() {
+            let url = url.to_string();
+            let _ = tokio::task::spawn_blocking(move || cache.put(&url, page)).await;
+        }
+    }
+
+    async fn try_download_page(
+        &self,
+        url: &str,
+        revalidate: bool,
+        info: &mut RequestInfo,
+    ) -> Result<RawPage, DownloadError> {
+        if let Some(html) = self
+            .middleware
+            .iter()
+            .find_map(|middleware| middleware.intercept(url))
+        {
+            info.bytes = html.len();
+            return Ok(RawPage {
+                html,
+                fetched_at: chrono::Utc::now(),
+                final_url: url::Url::parse(url).map_err(|_| DownloadError::InvalidEventUrl)?,
+                headers: vec![],
+            });
+        }
+        let mut request = self.http.get(url);
+        let cached = if revalidate {
+            self.cache_get(url).await
+        } else {
+            None
+        };
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let mut resp = self.send(url, request, info).await?;
+        let fetched_at = chrono::Utc::now();
+        let final_url = resp.url().clone();
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            let cached =
+                cached.ok_or_else(|| crate::download::status_error(&final_url, 304, None))?;
+            info.cache_hit = true;
+            #[cfg(feature = "tracing")]
+            tracing::debug!("page not modified, using cached copy");
+            return Ok(RawPage {
+                html: cached.body,
+                fetched_at,
+                final_url,
+                headers,
+            });
+        }
+        let header = |name| {
+            resp.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+        if matches!(resp.content_length(), Some(length) if length > self.max_body_size as u64) {
+            return Err(DownloadError::ResponseTooLarge { url: final_url });
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = resp.chunk().await.map_err(DownloadError::network(url))? {
+            if body.len() + chunk.len() > self.max_body_size {
+                return Err(DownloadError::ResponseTooLarge { url: final_url });
+            }
+            body.extend_from_slice(&chunk);
+            info.bytes = body.len();
+        }
+        let body = self.middleware.iter().fold(
+            String::from_utf8_lossy(&body).into_owned(),
+            |body, middleware| middleware.on_body(url, body),
+        );
+        if etag.is_some() || last_modified.is_some() {
+            self.cache_put(
+                url,
+                CachedPage {
+                    body: body.clone(),
+                    etag,
+                    last_modified,
+                },
+            )
+            .await;
+        }
+        Ok(RawPage {
+            html: body,
+            fetched_at,
+            final_url,
+            headers,
+        })
+    }
+}
+
+/// Compares the validators of a snapshot to the headers of a new response
+pub(crate) fn changed_since(previous: &RawPage, headers: &reqwest::header::HeaderMap) -> bool {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    let compare = |previous: Option<&str>, current: Option<&str>| match (previous, current) {
+        (Some(previous), Some(current)) => Some(previous != current),
+        _ => None,
+    };
+    compare(previous.header("etag"), header(reqwest::header::ETAG))
+        .or_else(|| {
+            compare(
+                previous.header("last-modified"),
+                header(reqwest::header::LAST_MODIFIED),
+            )
+        })
+        .or_else(|| {
+            compare(
+                previous.header("content-length"),
+                header(reqwest::header::CONTENT_LENGTH),
+            )
+        })
+        .unwrap_or(true)
+}
+
+/// The site redirects links of removed events to the home page
+///
+/// The redirect is recognized both when it was followed and, with a redirect policy that doesn't
+/// follow it, from the `Location` header of the 3xx response.
+pub(crate) fn is_redirect_to_home(
+    base: &url::Url,
+    requested: &str,
+    final_url: &url::Url,
+    status: u16,
+    location: Option<&str>,
+) -> bool {
+    let target = match location {
+        Some(location) if (300..400).contains(&status) => match final_url.join(location) {
+            Ok(target) => target,
+            Err(_) => return false,
+        },
+        _ => final_url.clone(),
+    };
+    let is_home = |url: &url::Url| matches!(crate::event::site_path(base, url), Some("" | "/"));
+    is_home(&target) && matches!(url::Url::parse(requested), Ok(requested) if !is_home(&requested))
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::builder()
+            .build()
+            .expect("failed to initialize the HTTP client")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    http: Option<reqwest::Client>,
+    retry_policy: RetryPolicy,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<url::Url>,
+    user_agent: Option<String>,
+    accept_language: Option<String>,
+    requests_per_minute: Option<u32>,
+    cache: Option<Arc<dyn CacheStore>>,
+    max_body_size: Option<usize>,
+    observer: Option<Arc<dyn RequestObserver>>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    base_url: Option<url::Url>,
+    redirect_policy: Option<reqwest::redirect::Policy>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    http1_only: bool,
+    http2_prior_knowledge: bool,
+    http2_adaptive_window: Option<bool>,
+    #[cfg(feature = "gzip")]
+    gzip: Option<bool>,
+    #[cfg(feature = "brotli")]
+    brotli: Option<bool>,
+    #[cfg(feature = "deflate")]
+    deflate: Option<bool>,
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    root_certificates: Vec<reqwest::Certificate>,
+    #[cfg(feature = "cookies")]
+    cookie_jar: Option<Arc<crate::cookies::FileCookieJar>>,
+}
+
+impl ClientBuilder {
+    /// Uses a preconfigured HTTP client, e.g. with default headers or custom TLS settings
+    ///
+    /// The connection settings of this builder, like the connect timeout, are not applied to it.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Maximum duration of a single request, from connecting until the whole page is received
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sends all requests through the given HTTP or HTTPS proxy
+    ///
+    /// With the `socks` feature, `socks5://` and `socks5h://` proxies are supported as well.
+    /// Without an explicit proxy, the `HTTP_PROXY` and `HTTPS_PROXY` environment variables are
+    /// used.
+    pub fn proxy(mut self, proxy: url::Url) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Sends all requests through the given SOCKS5 proxy, e.g. Tor at `127.0.0.1:9050`
+    ///
+    /// With `remote_dns`, host names are resolved by the proxy instead of locally, so that DNS
+    /// lookups don't bypass it.
+    #[cfg(feature = "socks")]
+    pub fn socks5_proxy(self, address: std::net::SocketAddr, remote_dns: bool) -> Self {
+        let scheme = if remote_dns { "socks5h" } else { "socks5" };
+        let proxy = url::Url::parse(&format!("{scheme}://{address}"))
+            .expect("socket address forms a valid URL");
+        self.proxy(proxy)
+    }
+
+    /// User-Agent header sent with every request, which defaults to `datumprikker/<version>`
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Accept-Language header sent with every request, e.g. `nl`
+    ///
+    /// The texts on the pages depend on the negotiated language, so pinning it makes parsing
+    /// independent of the language detection of the site.
+    pub fn accept_language(mut self, accept_language: impl Into<String>) -> Self {
+        self.accept_language = Some(accept_language.into());
+        self
+    }
+
+    /// Limits the number of requests per minute, shared by all clones of the client
+    pub fn rate_limit(mut self, requests_per_minute: u32) -> Self {
+        self.requests_per_minute = Some(requests_per_minute);
+        self
+    }
+
+    /// Stores downloaded pages, so that unchanged pages are revalidated instead of downloaded
+    pub fn cache(mut self, cache: impl CacheStore + 'static) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Largest page in bytes that is downloaded, which defaults to 10 MiB
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = Some(max_body_size);
+        self
+    }
+
+    /// Keeps cookies in the given jar, so that sessions are kept between runs
+    #[cfg(feature = "cookies")]
+    pub fn cookie_jar(mut self, cookie_jar: Arc<crate::cookies::FileCookieJar>) -> Self {
+        self.cookie_jar = Some(cookie_jar);
+        self
+    }
+
+    /// Reports every request to the observer, e.g. to collect metrics
+    pub fn observer(mut self, observer: impl RequestObserver + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Adds middleware, which is called after the middleware that was added before
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Controls which redirects are followed, which defaults to at most 10 redirects
+    pub fn redirect_policy(mut self, redirect_policy: reqwest::redirect::Policy) -> Self {
+        self.redirect_policy = Some(redirect_policy);
+        self
+    }
+
+    /// How long an unused connection is kept open for reuse, which defaults to 90 seconds
+    pub fn pool_idle_timeout(mut self, pool_idle_timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(pool_idle_timeout);
+        self
+    }
+
+    /// Maximum number of unused connections that are kept open to a single host
+    pub fn pool_max_idle_per_host(mut self, pool_max_idle_per_host: usize) -> Self {
+        self.pool_max_idle_per_host = Some(pool_max_idle_per_host);
+        self
+    }
+
+    /// Only uses HTTP/1, also when the server offers HTTP/2
+    pub fn http1_only(mut self) -> Self {
+        self.http1_only = true;
+        self
+    }
+
+    /// Uses HTTP/2 without negotiating it first, which requires a server that supports it
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.http2_prior_knowledge = true;
+        self
+    }
+
+    /// Adapts the HTTP/2 flow control window to the measured bandwidth and latency
+    pub fn http2_adaptive_window(mut self, enable: bool) -> Self {
+        self.http2_adaptive_window = Some(enable);
+        self
+    }
+
+    /// Trusts an additional root certificate, e.g. of a TLS-intercepting proxy
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Negotiates gzip compression, which is enabled by default with the `gzip` feature
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.gzip = Some(enable);
+        self
+    }
+
+    /// Negotiates brotli compression, which is enabled by default with the `brotli` feature
+    #[cfg(feature = "brotli")]
+    pub fn brotli(mut self, enable: bool) -> Self {
+        self.brotli = Some(enable);
+        self
+    }
+
+    /// Negotiates deflate compression, which is enabled by default with the `deflate` feature
+    #[cfg(feature = "deflate")]
+    pub fn deflate(mut self, enable: bool) -> Self {
+        self.deflate = Some(enable);
+        self
+    }
+
+    /// Location of the site, which defaults to `https://datumprikker.nl/`; used to look up events
+    /// by code and to recognize event URLs, e.g. to point the client at a test server
+    pub fn base_url(mut self, base_url: url::Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    pub fn build(self) -> Result<Client, DownloadError> {
+        let http = match self.http {
+            Some(http) => http,
+            None => {
+                let user_agent = self
+                    .user_agent
+                    .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+                let mut builder = reqwest::Client::builder().user_agent(user_agent);
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    let proxy = reqwest::Proxy::all(proxy).map_err(DownloadError::ClientError)?;
+                    builder = builder.proxy(proxy);
+                }
+                if let Some(pool_idle_timeout) = self.pool_idle_timeout {
+                    builder = builder.pool_idle_timeout(pool_idle_timeout);
+                }
+                if let Some(pool_max_idle_per_host) = self.pool_max_idle_per_host {
+                    builder = builder.pool_max_idle_per_host(pool_max_idle_per_host);
+                }
+                if self.http1_only {
+                    builder = builder.http1_only();
+                }
+                if self.http2_prior_knowledge {
+                    builder = builder.http2_prior_knowledge();
+                }
+                if let Some(enable) = self.http2_adaptive_window {
+                    builder = builder.http2_adaptive_window(enable);
+                }
+                #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+                for certificate in self.root_certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+                #[cfg(feature = "gzip")]
+                if let Some(enable) = self.gzip {
+                    builder = builder.gzip(enable);
+                }
+                #[cfg(feature = "brotli")]
+                if let Some(enable) = self.brotli {
+                    builder = builder.brotli(enable);
+                }
+                #[cfg(feature = "deflate")]
+                if let Some(enable) = self.deflate {
+                    builder = builder.deflate(enable);
+                }
+                if let Some(redirect_policy) = self.redirect_policy {
+                    builder = builder.redirect(redirect_policy);
+                }
+                #[cfg(feature = "cookies")]
+                if let Some(cookie_jar) = self.cookie_jar {
+                    builder = builder.cookie_provider(cookie_jar);
+                }
+                builder.build().map_err(DownloadError::ClientError)?
+            }
+        };
+        Ok(Client {
+            http,
+            retry_policy: self.retry_policy,
+            timeout: self.timeout,
+            rate_limiter: self
+                .requests_per_minute
+                .map(|requests| Arc::new(RateLimiter::per_minute(requests))),
+            cache: self.cache,
+            max_body_size: self.max_body_size.unwrap_or(DEFAULT_MAX_BODY_SIZE),
+            observer: self.observer,
+            middleware: self.middleware,
+            base_url: match self.base_url {
+                Some(base_url) => base_url,
+                None => url::Url::parse(crate::event::DEFAULT_BASE_URL)
+                    .expect("default base URL is valid"),
+            },
+            accept_language: self.accept_language,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::MemoryCache;
+    use crate::client::{changed_since, is_redirect_to_home, Client};
+    use crate::download::DownloadError;
+    use crate::event_overview_page::ParsePageError;
+    use crate::raw_page::RawPage;
+    use crate::retry::RetryPolicy;
+    use crate::test_server::{response, serve};
+    use chrono::Utc;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn redirect_to_home() {
+        let base = url::Url::parse("https://datumprikker.nl/").unwrap();
+        let home = url::Url::parse("https://datumprikker.nl/").unwrap();
+        let event = url::Url::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap();
+        let requested = "https://datumprikker.nl/afspraak/overzicht/abc";
+        assert!(is_redirect_to_home(&base, requested, &home, 200, None));
+        assert!(!is_redirect_to_home(&base, requested, &event, 200, None));
+        assert!(!is_redirect_to_home(
+            &base,
+            "https://datumprikker.nl/",
+            &home,
+            200,
+            None
+        ));
+    }
+
+    #[test]
+    fn unfollowed_redirect_to_home() {
+        let base = url::Url::parse("https://datumprikker.nl/").unwrap();
+        let event = url::Url::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap();
+        let requested = "https://datumprikker.nl/afspraak/overzicht/abc";
+        assert!(is_redirect_to_home(
+            &base,
+            requested,
+            &event,
+            302,
+            Some("/")
+        ));
+        assert!(is_redirect_to_home(
+            &base,
+            requested,
+            &event,
+            302,
+            Some("https://www.datumprikker.nl/")
+        ));
+        assert!(!is_redirect_to_home(
+            &base,
+            requested,
+            &event,
+            301,
+            Some("/afspraak/overzicht/abc/")
+        ));
+        assert!(!is_redirect_to_home(
+            &base,
+            requested,
+            &event,
+            200,
+            Some("/")
+        ));
+    }
+
+    #[test]
+    fn redirect_to_home_of_mirror() {
+        let base = url::Url::parse("http://localhost:8080/mirror/").unwrap();
+        let home = url::Url::parse("http://localhost:8080/mirror/").unwrap();
+        let root = url::Url::parse("http://localhost:8080/").unwrap();
+        let requested = "http://localhost:8080/mirror/afspraak/overzicht/abc";
+        assert!(is_redirect_to_home(&base, requested, &home, 200, None));
+        assert!(!is_redirect_to_home(&base, requested, &root, 200, None));
+    }
+
+    #[test]
+    fn changed_since_snapshot() {
+        let snapshot = |headers: &[(&str, &str)]| RawPage {
+            html: String::new(),
+            fetched_at: Utc::now(),
+            final_url: url::Url::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert("etag", HeaderValue::from_static("\"v2\""));
+        headers.insert("content-length", HeaderValue::from_static("100"));
+
+        assert!(changed_since(&snapshot(&[("etag", "\"v1\"")]), &headers));
+        assert!(!changed_since(&snapshot(&[("ETag", "\"v2\"")]), &headers));
+        assert!(!changed_since(
+            &snapshot(&[("content-length", "100")]),
+            &headers
+        ));
+        assert!(changed_since(
+            &snapshot(&[("content-length", "99")]),
+            &headers
+        ));
+        assert!(changed_since(&snapshot(&[]), &headers));
+    }
+
+    #[tokio::test]
+    async fn response_too_large() {
+        let page = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let (base, server) = serve(vec![response("200 OK", &[], page)]);
+        let client = Client::builder().max_body_size(1024).build().unwrap();
+        let url = base.join("afspraak/overzicht/fewqvuycnmvgnx25").unwrap();
+        let result = client.download_event(&url).await;
+        assert!(matches!(
+            result,
+            Err(DownloadError::ResponseTooLarge { url: error_url }) if error_url == url
+        ));
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn not_modified_returns_cached_page() {
+        let page = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let (base, server) = serve(vec![
+            response("200 OK", &[("ETag", "\"v1\"")], page),
+            response("304 Not Modified", &[("ETag", "\"v1\"")], ""),
+        ]);
+        let client = Client::builder().cache(MemoryCache::new()).build().unwrap();
+        let url = base.join("afspraak/overzicht/fewqvuycnmvgnx25").unwrap();
+        let first = client.fetch_page(&url).await.unwrap();
+        let second = client.fetch_page(&url).await.unwrap();
+        assert_eq!(first.html, page);
+        assert_eq!(second.html, page);
+        let requests = server.join().unwrap();
+        assert!(!requests[0].to_lowercase().contains("if-none-match"));
+        assert!(requests[1].to_lowercase().contains("if-none-match: \"v1\""));
+    }
+
+    #[tokio::test]
+    async fn retries_share_one_budget() {
+        let page = include_str!("../data/afspraak_overzicht_in_progress.html");
+        // The connection is closed halfway the body, then the page comes back broken
+        let truncated = response("200 OK", &[], page).replace(page, &page[..page.len() / 2]);
+        let broken = response("200 OK", &[], "<html></html>");
+        let (base, server) = serve(vec![truncated, broken]);
+        let client = Client::builder()
+            .retry_policy(RetryPolicy {
+                max_attempts: 2,
+                base_delay: std::time::Duration::ZERO,
+                jitter: std::time::Duration::ZERO,
+                retry_parse_errors: vec![ParsePageError::UnexpectedHtml],
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let url = base.join("afspraak/overzicht/fewqvuycnmvgnx25").unwrap();
+        let result = client.download_event(url).await;
+        assert!(matches!(
+            result,
+            Err(DownloadError::ParseError {
+                source: ParsePageError::UnexpectedHtml,
+                ..
+            })
+        ));
+        assert_eq!(server.join().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,218 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+/// Cookie jar that is stored in a file, so that cookies survive between runs
+///
+/// The file is rewritten every time the site sets a cookie.
+#[derive(Debug)]
+pub struct FileCookieJar {
+    path: PathBuf,
+    store: RwLock<cookie_store::CookieStore>,
+    /// Serializes saves, as they all write the same temporary file
+    save_lock: Mutex<()>,
+}
+
+impl FileCookieJar {
+    /// Opens the cookie jar at the given path, which is created when the first cookie is set
+    ///
+    /// A jar that can't be parsed is ignored, so the client starts with a new session instead of
+    /// failing to start.
+    pub fn open(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let store = match std::fs::File::open(&path) {
+            Ok(file) => match cookie_store::CookieStore::load_json(BufReader::new(file)) {
+                Ok(store) => store,
+                Err(_err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        path = %path.display(),
+                        error = %_err,
+                        "ignoring corrupt cookie jar"
+                    );
+                    Default::default()
+                }
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Default::default(),
+            Err(err) => return Err(err),
+        };
+        Ok(FileCookieJar {
+            path,
+            store: RwLock::new(store),
+            save_lock: Mutex::new(()),
+        })
+    }
+
+    /// Opens the cookie jar in the XDG data directory, e.g. `~/.local/share/datumprikker`
+    pub fn open_default() -> std::io::Result<Self> {
+        let path = Self::default_path().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no home directory found")
+        })?;
+        Self::open(path)
+    }
+
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share"))
+            })?;
+        Some(data_home.join("datumprikker").join("cookies.json"))
+    }
+
+    /// Writes all cookies to the file, including session cookies
+    ///
+    /// The cookies are written to a temporary file first, which then replaces the jar, so that a
+    /// crash while writing doesn't leave a corrupt jar behind.
+    pub fn save(&self) -> std::io::Result<()> {
+        let _guard = self.save_lock.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(directory) = self.path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+        let mut file = std::fs::File::create(&temporary)?;
+        // Expired cookies are skipped when the jar is opened again
+        self.store
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .save_incl_expired_and_nonpersistent_json(&mut file)
+            .map_err(std::io::Error::other)?;
+        file.sync_all()?;
+        std::fs::rename(temporary, &self.path)
+    }
+}
+
+impl reqwest::cookie::CookieStore for FileCookieJar {
+    fn set_cookies(
+        &self,
+        cookie_headers: &mut dyn Iterator<Item = &reqwest::header::HeaderValue>,
+        url: &url::Url,
+    ) {
+        let cookies = cookie_headers.filter_map(|value| {
+            cookie_store::RawCookie::parse(value.to_str().ok()?.to_string()).ok()
+        });
+        self.store
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .store_response_cookies(cookies, url);
+        // Storing cookies is best effort, a failure only means the next run starts a new session
+        let _ = self.save();
+    }
+
+    fn cookies(&self, url: &url::Url) -> Option<reqwest::header::HeaderValue> {
+        let header = self
+            .store
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if header.is_empty() {
+            None
+        } else {
+            reqwest::header::HeaderValue::from_str(&header).ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookies::FileCookieJar;
+    use reqwest::cookie::CookieStore;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn cookies_survive_reopening() {
+        let path =
+            std::env::temp_dir().join(format!("datumprikker-cookies-{}.json", std::process::id()));
+        let url = url::Url::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap();
+        let jar = FileCookieJar::open(&path).unwrap();
+        assert_eq!(jar.cookies(&url), None);
+        let header = HeaderValue::from_static("session=1234; Max-Age=3600; Path=/");
+        jar.set_cookies(&mut std::iter::once(&header), &url);
+
+        let jar = FileCookieJar::open(&path).unwrap();
+        assert_eq!(
+            jar.cookies(&url),
+            Some(HeaderValue::from_static("session=1234"))
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn session_cookies_survive_reopening() {
+        let path = std::env::temp_dir().join(format!(
+            "datumprikker-session-cookies-{}.json",
+            std::process::id()
+        ));
+        let url = url::Url::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap();
+        let jar = FileCookieJar::open(&path).unwrap();
+        let header = HeaderValue::from_static("session=5678; Path=/");
+        jar.set_cookies(&mut std::iter::once(&header), &url);
+
+        let jar = FileCookieJar::open(&path).unwrap();
+        assert_eq!(
+            jar.cookies(&url),
+            Some(HeaderValue::from_static("session=5678"))
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_jar_starts_empty() {
+        let path = std::env::temp_dir().join(format!(
+            "datumprikker-corrupt-cookies-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, "{\"cookies\": [").unwrap();
+        let url = url::Url::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap();
+        let jar = FileCookieJar::open(&path).unwrap();
+        assert_eq!(jar.cookies(&url), None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn concurrent_saves() {
+        let path = std::env::temp_dir().join(format!(
+            "datumprikker-concurrent-cookies-{}.json",
+            std::process::id()
+        ));
+        let url = url::Url::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap();
+        let jar = FileCookieJar::open(&path).unwrap();
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let jar = &jar;
+                let url = &url;
+                scope.spawn(move || {
+                    let header = HeaderValue::from_str(&format!("c{}={}; Path=/", i, i)).unwrap();
+                    jar.set_cookies(&mut std::iter::once(&header), url);
+                });
+            }
+        });
+
+        let jar = FileCookieJar::open(&path).unwrap();
+        let cookies = jar.cookies(&url).unwrap();
+        assert_eq!(cookies.to_str().unwrap().split("; ").count(), 8);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,174 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use crate::event::{DateRange, Event, EventStatus, Participant, Vote};
+
+/// Single difference between two versions of an event
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventChange {
+    TitleChanged {
+        old: String,
+        new: String,
+    },
+    StatusChanged {
+        old: EventStatus,
+        new: EventStatus,
+    },
+    FinalDateSet(DateRange),
+    FinalDateRemoved(DateRange),
+    SlotAdded(DateRange),
+    SlotRemoved(DateRange),
+    ParticipantAdded(Participant),
+    ParticipantRemoved(String),
+    /// The participant voted differently for at least one date that exists in both versions
+    VotesChanged {
+        participant: String,
+    },
+}
+
+/// Differences between two versions of an event, e.g. to notify about new responses
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventDiff {
+    pub changes: Vec<EventChange>,
+}
+
+impl EventDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Event {
+    /// Lists what changed between two versions of the same event
+    ///
+    /// Participants are matched by name and proposed dates by their range.
+    pub fn diff(old: &Event, new: &Event) -> EventDiff {
+        let mut changes = Vec::new();
+        if old.title != new.title {
+            changes.push(EventChange::TitleChanged {
+                old: old.title.clone(),
+                new: new.title.clone(),
+            });
+        }
+        if old.status != new.status {
+            changes.push(EventChange::StatusChanged {
+                old: old.status,
+                new: new.status,
+            });
+        }
+        for date in &new.final_dates {
+            if !old.final_dates.contains(date) {
+                changes.push(EventChange::FinalDateSet(date.clone()));
+            }
+        }
+        for date in &old.final_dates {
+            if !new.final_dates.contains(date) {
+                changes.push(EventChange::FinalDateRemoved(date.clone()));
+            }
+        }
+        let old_slots: Vec<&DateRange> =
+            old.proposed_dates.iter().map(|slot| &slot.range).collect();
+        let new_slots: Vec<&DateRange> =
+            new.proposed_dates.iter().map(|slot| &slot.range).collect();
+        for range in &new_slots {
+            if !old_slots.contains(range) {
+                changes.push(EventChange::SlotAdded((*range).clone()));
+            }
+        }
+        for range in &old_slots {
+            if !new_slots.contains(range) {
+                changes.push(EventChange::SlotRemoved((*range).clone()));
+            }
+        }
+        for participant in &new.participants {
+            match old.participants.iter().find(|p| p.name == participant.name) {
+                None => changes.push(EventChange::ParticipantAdded(participant.clone())),
+                Some(previous) => {
+                    let vote = |participant: &Participant, slots: &[&DateRange], range| {
+                        let index = slots.iter().position(|slot| *slot == range)?;
+                        participant.availability.get(index).copied()
+                    };
+                    let changed = old_slots
+                        .iter()
+                        .filter(|range| new_slots.contains(range))
+                        .any(|range| {
+                            vote(previous, &old_slots, *range).unwrap_or(Vote::NoAnswer)
+                                != vote(participant, &new_slots, *range).unwrap_or(Vote::NoAnswer)
+                        });
+                    if changed {
+                        changes.push(EventChange::VotesChanged {
+                            participant: participant.name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        for participant in &old.participants {
+            if !new.participants.iter().any(|p| p.name == participant.name) {
+                changes.push(EventChange::ParticipantRemoved(participant.name.clone()));
+            }
+        }
+        EventDiff { changes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::EventChange;
+    use crate::event::{Event, EventStatus, Vote};
+    use crate::event_overview_page::parse_page;
+
+    #[test]
+    fn diff() {
+        let new = parse_page(include_str!("../data/afspraak_overzicht_finalized.html")).unwrap();
+        assert!(Event::diff(&new, &new).is_empty());
+
+        let mut old = new.clone();
+        old.title = String::from("Old title");
+        old.final_dates.clear();
+        old.status = EventStatus::Open;
+        let added = old.participants.pop().unwrap();
+        old.participants[0].availability[0] = Vote::No;
+        let added_slot = old.proposed_dates.pop().unwrap().range;
+        assert_eq!(
+            Event::diff(&old, &new).changes,
+            vec![
+                EventChange::TitleChanged {
+                    old: String::from("Old title"),
+                    new: new.title.clone(),
+                },
+                EventChange::StatusChanged {
+                    old: EventStatus::Open,
+                    new: new.status,
+                },
+                EventChange::FinalDateSet(new.final_dates[0].clone()),
+                EventChange::SlotAdded(added_slot.clone()),
+                EventChange::VotesChanged {
+                    participant: new.participants[0].name.clone(),
+                },
+                EventChange::ParticipantAdded(added.clone()),
+            ]
+        );
+        assert!(Event::diff(&new, &old)
+            .changes
+            .contains(&EventChange::ParticipantRemoved(added.name)));
+        assert!(Event::diff(&new, &old)
+            .changes
+            .contains(&EventChange::SlotRemoved(added_slot)));
+    }
+}
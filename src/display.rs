@@ -0,0 +1,165 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use crate::event::{DateRange, Event};
+use chrono::TimeZone;
+use std::fmt;
+
+/// Human-readable rendering of a [`DateRange`] in a specific time zone
+#[derive(Debug, Clone)]
+pub struct DateRangeDisplay<'a, T: TimeZone> {
+    range: &'a DateRange,
+    timezone: T,
+}
+
+/// Human-readable summary of an [`Event`], with its dates in a specific time zone
+#[derive(Debug, Clone)]
+pub struct EventDisplay<'a, T: TimeZone> {
+    event: &'a Event,
+    timezone: T,
+}
+
+impl DateRange {
+    /// Renders the range in the given time zone, e.g. `2022-06-03 18:00–21:00 CEST`
+    pub fn display_in<T: TimeZone>(&self, timezone: T) -> DateRangeDisplay<'_, T> {
+        DateRangeDisplay {
+            range: self,
+            timezone,
+        }
+    }
+
+    /// Renders the range in the time zone of the system
+    pub fn display_local(&self) -> DateRangeDisplay<'_, chrono::Local> {
+        self.display_in(chrono::Local)
+    }
+}
+
+impl Event {
+    /// Renders the event with its dates in the given time zone
+    pub fn display_in<T: TimeZone>(&self, timezone: T) -> EventDisplay<'_, T> {
+        EventDisplay {
+            event: self,
+            timezone,
+        }
+    }
+
+    /// Renders the event with its dates in the time zone of the system
+    pub fn display_local(&self) -> EventDisplay<'_, chrono::Local> {
+        self.display_in(chrono::Local)
+    }
+}
+
+impl<T: TimeZone> fmt::Display for DateRangeDisplay<'_, T>
+where
+    T::Offset: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let start = self.range.start.with_timezone(&self.timezone);
+        let end = self.range.end.with_timezone(&self.timezone);
+        write!(f, "{}", start.format("%Y-%m-%d %H:%M"))?;
+        if end.date_naive() != start.date_naive() {
+            write!(f, " – {}", end.format("%Y-%m-%d %H:%M"))?;
+        } else if end != start {
+            write!(f, "–{}", end.format("%H:%M"))?;
+        }
+        write!(f, " {}", end.offset())
+    }
+}
+
+impl<T: TimeZone> fmt::Display for EventDisplay<'_, T>
+where
+    T::Offset: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.event.title)?;
+        writeln!(f, "{}", self.event.canonical_url)?;
+        if self.event.final_dates.is_empty() {
+            write!(f, "no final date selected")?;
+        }
+        for (index, final_date) in self.event.final_dates.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            let final_date = final_date.display_in(self.timezone.clone());
+            write!(f, "final date: {final_date}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the range in UTC
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_in(chrono::Utc).fmt(f)
+    }
+}
+
+/// Renders the event with its dates in the time zone of the organizer, or in UTC if unknown
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_in(self.timezone.unwrap_or(chrono_tz::UTC))
+            .fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{DateRange, Event};
+    use crate::event_url::EventUrl;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    #[test]
+    fn date_range() {
+        let start = Utc.with_ymd_and_hms(2022, 6, 3, 16, 0, 0).unwrap();
+        let range = |end| DateRange { start, end };
+        let evening = Utc.with_ymd_and_hms(2022, 6, 3, 19, 0, 0).unwrap();
+        let next_day = Utc.with_ymd_and_hms(2022, 6, 4, 1, 0, 0).unwrap();
+        assert_eq!(range(start).to_string(), "2022-06-03 16:00 UTC");
+        assert_eq!(range(evening).to_string(), "2022-06-03 16:00–19:00 UTC");
+        assert_eq!(
+            range(next_day).to_string(),
+            "2022-06-03 16:00 – 2022-06-04 01:00 UTC"
+        );
+        assert_eq!(
+            range(evening)
+                .display_in(chrono_tz::Europe::Amsterdam)
+                .to_string(),
+            "2022-06-03 18:00–21:00 CEST"
+        );
+    }
+
+    #[test]
+    fn event() {
+        let start = Utc.with_ymd_and_hms(2022, 6, 3, 16, 0, 0).unwrap();
+        let url = EventUrl::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap();
+        let event = Event::builder(url.clone())
+            .title("Game night")
+            .timezone(chrono_tz::Europe::Amsterdam)
+            .final_date(DateRange { start, end: start })
+            .build();
+        assert_eq!(
+            event.to_string(),
+            "Game night\nhttps://datumprikker.nl/afspraak/overzicht/abc\n\
+             final date: 2022-06-03 18:00 CEST"
+        );
+        let event = Event::builder(url).title("Game night").build();
+        assert_eq!(
+            event.to_string(),
+            "Game night\nhttps://datumprikker.nl/afspraak/overzicht/abc\nno final date selected"
+        );
+    }
+}
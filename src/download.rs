@@ -15,20 +15,204 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use crate::event::Event;
+#[cfg(feature = "download")]
+use crate::client::Client;
+#[cfg(feature = "download")]
+use crate::event::{AdminEvent, Contact, Event};
 use crate::event_overview_page::ParsePageError;
+#[cfg(feature = "download")]
+use crate::raw_page::RawPage;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum DownloadError {
-    #[error("network error during download")]
-    NetworkError(#[from] reqwest::Error),
-    #[error("parse error of page")]
-    ParseError(#[from] ParsePageError),
+    #[cfg(feature = "download")]
+    #[error("network error during download of {url}")]
+    NetworkError {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[cfg(feature = "download")]
+    #[error("failed to initialize the HTTP client")]
+    ClientError(#[source] reqwest::Error),
+    #[error("datumprikker limits the number of requests, at {url}")]
+    RateLimited {
+        url: url::Url,
+        /// Time to wait before the next request, as requested by the site
+        retry_after: Option<Duration>,
+    },
+    #[error("unexpected HTTP status {status} for {url}")]
+    HttpStatus { url: String, status: u16 },
+    #[error("parse error of page {url}")]
+    ParseError {
+        url: url::Url,
+        #[source]
+        source: ParsePageError,
+    },
+    #[error("datumprikker is temporarily unavailable, at {url}")]
+    ServiceUnavailable { url: url::Url },
+    #[error("URL does not point to a datumprikker event")]
+    InvalidEventUrl,
+    #[error("page {url} is larger than the maximum body size")]
+    ResponseTooLarge { url: url::Url },
+    #[error("network error of the HTTP backend")]
+    TransportError(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
 
-pub async fn download_event(url: &str) -> Result<Event, DownloadError> {
-    let resp = reqwest::get(url).await?.text().await?;
-    let event = crate::event_overview_page::parse_page(resp.as_str())?;
-    Ok(event)
+impl DownloadError {
+    /// A maintenance page is reported as [`DownloadError::ServiceUnavailable`], so that callers
+    /// back off instead of reporting a broken parser
+    pub(crate) fn parse(url: &url::Url) -> impl FnOnce(ParsePageError) -> DownloadError + '_ {
+        move |source| match source {
+            ParsePageError::Maintenance => DownloadError::ServiceUnavailable { url: url.clone() },
+            source => DownloadError::ParseError {
+                url: url.clone(),
+                source,
+            },
+        }
+    }
+
+    #[cfg(feature = "download")]
+    pub(crate) fn network(url: &str) -> impl FnOnce(reqwest::Error) -> DownloadError + '_ {
+        move |source| DownloadError::NetworkError {
+            url: url.to_string(),
+            source,
+        }
+    }
+}
+
+/// Parses the URL of a download, so that errors can report it
+pub(crate) fn parse_download_url(url: &str) -> Result<url::Url, DownloadError> {
+    url::Url::parse(url).map_err(|_err| DownloadError::InvalidEventUrl)
+}
+
+/// Parses a `Retry-After` header, which is either a number of seconds or an HTTP date
+#[cfg_attr(not(any(feature = "download", feature = "ureq")), allow(dead_code))]
+pub(crate) fn parse_retry_after(
+    value: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    Some(
+        (date.with_timezone(&chrono::Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Error for a response that isn't successful, shared by all HTTP backends
+#[cfg_attr(not(any(feature = "download", feature = "ureq")), allow(dead_code))]
+pub(crate) fn status_error(
+    url: &url::Url,
+    status: u16,
+    retry_after: Option<&str>,
+) -> DownloadError {
+    match status {
+        503 => DownloadError::ServiceUnavailable { url: url.clone() },
+        429 => DownloadError::RateLimited {
+            url: url.clone(),
+            retry_after: retry_after.and_then(|value| parse_retry_after(value, chrono::Utc::now())),
+        },
+        status => DownloadError::HttpStatus {
+            url: url.to_string(),
+            status,
+        },
+    }
+}
+
+/// Client shared by the free functions, so that sequential downloads reuse connections
+///
+/// A client that fails to initialize is not stored, so the next call tries again.
+#[cfg(feature = "download")]
+fn default_client() -> Result<&'static Client, DownloadError> {
+    static CLIENT: std::sync::OnceLock<Client> = std::sync::OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = Client::builder().build()?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+#[cfg(feature = "download")]
+pub async fn download_event(url: impl AsRef<str>) -> Result<Event, DownloadError> {
+    default_client()?.download_event(url).await
+}
+
+/// Downloads the page without parsing it, e.g. to archive the HTML next to the event
+#[cfg(feature = "download")]
+pub async fn fetch_page(url: impl AsRef<str>) -> Result<RawPage, DownloadError> {
+    default_client()?.fetch_page(url).await
+}
+
+/// Downloads several events at once, with at most `max_concurrent` downloads in flight
+#[cfg(feature = "download")]
+pub async fn download_events<I>(urls: I, max_concurrent: usize) -> Vec<Result<Event, DownloadError>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    match default_client() {
+        Ok(client) => client.download_events(urls, max_concurrent).await,
+        Err(err) => {
+            // Errors can't be cloned, so every other URL tries to initialize the client again
+            let mut err = Some(err);
+            let mut results = vec![];
+            for url in urls {
+                results.push(match err.take() {
+                    Some(err) => Err(err),
+                    None => download_event(url).await,
+                });
+            }
+            results
+        }
+    }
+}
+
+/// Downloads the event with the given code, e.g. `fewqvuycnmvgnx25`
+#[cfg(feature = "download")]
+pub async fn download_event_by_code(code: &str) -> Result<Event, DownloadError> {
+    default_client()?.download_event_by_code(code).await
+}
+
+/// Downloads the event using the organizer's link, which shows the invitees as well
+#[cfg(feature = "download")]
+pub async fn download_event_admin(url: impl AsRef<str>) -> Result<AdminEvent, DownloadError> {
+    default_client()?.download_event_admin(url).await
+}
+
+/// Downloads the contact details of the participants using the organizer's link
+#[cfg(feature = "download")]
+pub async fn export_participants(url: impl AsRef<str>) -> Result<Vec<Contact>, DownloadError> {
+    default_client()?.export_participants(url).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::download::parse_retry_after;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use std::time::Duration;
+
+    #[test]
+    fn retry_after() {
+        let now = Utc.with_ymd_and_hms(2022, 7, 18, 17, 0, 0).unwrap();
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Mon, 18 Jul 2022 17:01:30 GMT", now),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            parse_retry_after("Mon, 18 Jul 2022 16:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
 }
@@ -15,19 +15,585 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+use crate::event_url::EventUrl;
 use chrono::DateTime;
 use chrono::Utc;
 
+/// Time range of a date option; when the page shows no end time, `end` is equal to `start`
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DateRange {
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
 }
 
+impl DateRange {
+    pub fn duration(&self) -> chrono::Duration {
+        self.end - self.start
+    }
+
+    /// Whether both ranges share a moment; ranges that only touch don't overlap
+    pub fn overlaps(&self, other: &DateRange) -> bool {
+        if self.duration().is_zero() {
+            return other.contains(&self.start);
+        }
+        if other.duration().is_zero() {
+            return self.contains(&other.start);
+        }
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Whether the moment lies in the range, which includes the start but not the end
+    ///
+    /// A range without end time only contains its start.
+    pub fn contains(&self, moment: &DateTime<Utc>) -> bool {
+        if self.duration().is_zero() {
+            return *moment == self.start;
+        }
+        self.start <= *moment && *moment < self.end
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Slot {
+    pub range: DateRange,
+    pub yes: u32,
+    pub maybe: u32,
+    pub no: u32,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Vote {
+    Yes,
+    IfNeedBe,
+    No,
+    NoAnswer,
+}
+
+impl Vote {
+    /// Weight of the vote as used by the site to rank the proposed dates
+    pub fn score(&self) -> u32 {
+        match self {
+            Vote::Yes => 100,
+            Vote::IfNeedBe => 50,
+            Vote::No | Vote::NoAnswer => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Participant {
+    pub name: String,
+    pub email: Option<String>,
+    /// Vote for each proposed date, in the order shown on the page
+    pub availability: Vec<Vote>,
+    /// Note left with the vote for each proposed date
+    pub notes: Vec<Option<String>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Organizer {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventStatus {
+    /// No date is picked yet, participants can still respond
+    Open,
+    /// A final date is picked
+    Finalized,
+    /// The final date lies in the past
+    ///
+    /// The page only marks this with a Dutch heading, so it is only detected on Dutch pages. On
+    /// pages in other languages a past event is reported as [`EventStatus::Finalized`]; compare
+    /// [`Event::final_dates`] with the current time to find out whether it is over.
+    Expired,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Language {
+    Dutch,
+    English,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Event {
-    pub canonical_url: String,
+    pub canonical_url: EventUrl,
     pub title: String,
-    pub final_date: Option<DateRange>,
-    pub open_registration_link: Option<String>,
+    pub final_dates: Vec<DateRange>,
+    pub open_registration_link: Option<EventUrl>,
+    pub participants: Vec<Participant>,
+    pub proposed_dates: Vec<Slot>,
+    /// Proposed date with the highest score according to the site; the earliest one on a tie
+    pub site_suggested_date: Option<DateRange>,
+    pub description: Option<String>,
+    pub organizer: Option<Organizer>,
+    pub location: Option<String>,
+    pub status: EventStatus,
+    /// Language the page was rendered in
+    pub language: Option<Language>,
+    /// Number of participants that responded, according to the page
+    pub participant_count: Option<u32>,
+    /// Links mentioned in the description
+    pub links: Vec<url::Url>,
+    /// Time zone the organizer entered the dates in
+    pub timezone: Option<chrono_tz::Tz>,
+}
+
+impl Event {
+    /// Starts an open event without dates or participants, e.g. to construct one in a test
+    pub fn builder(canonical_url: EventUrl) -> EventBuilder {
+        EventBuilder {
+            event: Event {
+                canonical_url,
+                title: String::new(),
+                final_dates: Vec::new(),
+                open_registration_link: None,
+                participants: Vec::new(),
+                proposed_dates: Vec::new(),
+                site_suggested_date: None,
+                description: None,
+                organizer: None,
+                location: None,
+                status: EventStatus::Open,
+                language: None,
+                participant_count: None,
+                links: Vec::new(),
+                timezone: None,
+            },
+        }
+    }
+
+    /// Short identifier of the event, e.g. `fewqvuycnmvgnx25`
+    pub fn code(&self) -> &str {
+        self.canonical_url.code()
+    }
+
+    pub fn availability_matrix(&self) -> AvailabilityMatrix {
+        AvailabilityMatrix {
+            participants: self
+                .participants
+                .iter()
+                .map(|participant| participant.name.clone())
+                .collect(),
+            slots: self
+                .proposed_dates
+                .iter()
+                .map(|slot| slot.range.clone())
+                .collect(),
+            cells: self
+                .participants
+                .iter()
+                .map(|participant| {
+                    participant
+                        .availability
+                        .iter()
+                        .enumerate()
+                        .map(|(slot, vote)| Availability {
+                            vote: *vote,
+                            note: participant.notes.get(slot).cloned().flatten(),
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EventBuilder {
+    event: Event,
+}
+
+impl EventBuilder {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.event.title = title.into();
+        self
+    }
+
+    /// Adds a final date and marks the event as finalized
+    pub fn final_date(mut self, final_date: DateRange) -> Self {
+        self.event.final_dates.push(final_date);
+        self.event.status = EventStatus::Finalized;
+        self
+    }
+
+    pub fn open_registration_link(mut self, link: EventUrl) -> Self {
+        self.event.open_registration_link = Some(link);
+        self
+    }
+
+    pub fn participant(mut self, participant: Participant) -> Self {
+        self.event.participants.push(participant);
+        self
+    }
+
+    pub fn proposed_date(mut self, slot: Slot) -> Self {
+        self.event.proposed_dates.push(slot);
+        self
+    }
+
+    pub fn site_suggested_date(mut self, date: DateRange) -> Self {
+        self.event.site_suggested_date = Some(date);
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.event.description = Some(description.into());
+        self
+    }
+
+    pub fn organizer(mut self, organizer: Organizer) -> Self {
+        self.event.organizer = Some(organizer);
+        self
+    }
+
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.event.location = Some(location.into());
+        self
+    }
+
+    pub fn status(mut self, status: EventStatus) -> Self {
+        self.event.status = status;
+        self
+    }
+
+    pub fn language(mut self, language: Language) -> Self {
+        self.event.language = Some(language);
+        self
+    }
+
+    pub fn participant_count(mut self, participant_count: u32) -> Self {
+        self.event.participant_count = Some(participant_count);
+        self
+    }
+
+    pub fn link(mut self, link: url::Url) -> Self {
+        self.event.links.push(link);
+        self
+    }
+
+    pub fn timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.event.timezone = Some(timezone);
+        self
+    }
+
+    pub fn build(self) -> Event {
+        self.event
+    }
+}
+
+/// Extracts the event code from an overview URL
+///
+/// Short links have a code of their own, which differs from the code of the event; resolve them
+/// with `Client::resolve_event_url` first.
+pub fn parse_event_code(url: &str) -> Option<String> {
+    code_from_path(url::Url::parse(url).ok()?.path())
+}
+
+fn code_from_path(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    match segments.as_slice() {
+        ["afspraak", "overzicht", code] => Some(code.to_string()),
+        _ => None,
+    }
+}
+
+pub(crate) const DEFAULT_BASE_URL: &str = "https://datumprikker.nl/";
+
+pub(crate) fn default_base_url() -> url::Url {
+    url::Url::parse(DEFAULT_BASE_URL).expect("default base URL is valid")
+}
+
+/// Converts an overview URL into its canonical form, e.g. without `www.` or a trailing slash
+///
+/// Returns `None` for URLs that don't point to an overview page on datumprikker.nl. Short links
+/// can't be converted without following them; use `Client::resolve_event_url` for those.
+pub fn normalize_event_url(url: &str) -> Option<url::Url> {
+    normalize_event_url_at(&default_base_url(), url)
+}
+
+/// Like [`normalize_event_url`], for the site hosted at `base`, e.g. a mirror or a test server
+pub fn normalize_event_url_at(base: &url::Url, url: &str) -> Option<url::Url> {
+    let url = url::Url::parse(url).ok()?;
+    event_url_at(base, &code_from_path(site_path(base, &url)?)?)
+}
+
+/// Path of the URL relative to the site hosted at `base`, or `None` if it points elsewhere
+pub(crate) fn site_path<'a>(base: &url::Url, url: &'a url::Url) -> Option<&'a str> {
+    let host = url.host_str()?;
+    let base_host = base.host_str()?;
+    if host != base_host && host.strip_prefix("www.") != Some(base_host) {
+        return None;
+    }
+    if url.port() != base.port() {
+        return None;
+    }
+    url.path().strip_prefix(base.path().trim_end_matches('/'))
+}
+
+/// Overview URL of the event with the given code
+///
+/// Returns `None` if the code contains characters other than ASCII letters and digits.
+pub fn event_url(code: &str) -> Option<url::Url> {
+    event_url_at(&default_base_url(), code)
+}
+
+/// Like [`event_url`], for the site hosted at `base`, e.g. a mirror or a test server
+pub fn event_url_at(base: &url::Url, code: &str) -> Option<url::Url> {
+    if code.is_empty() || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    base.join("afspraak/overzicht/").ok()?.join(code).ok()
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Availability {
+    pub vote: Vote,
+    pub note: Option<String>,
+}
+
+/// Votes of all participants for all proposed dates, indexed by participant and then by slot
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AvailabilityMatrix {
+    pub participants: Vec<String>,
+    pub slots: Vec<DateRange>,
+    pub cells: Vec<Vec<Availability>>,
+}
+
+impl AvailabilityMatrix {
+    pub fn get(&self, participant: usize, slot: usize) -> Option<Vote> {
+        Some(self.cells.get(participant)?.get(slot)?.vote)
+    }
+
+    pub fn note(&self, participant: usize, slot: usize) -> Option<&str> {
+        self.cells.get(participant)?.get(slot)?.note.as_deref()
+    }
+
+    /// Votes of all participants for a single slot
+    pub fn slot_votes(&self, slot: usize) -> Vec<Vote> {
+        self.cells
+            .iter()
+            .map(|cells| cells.get(slot).map_or(Vote::NoAnswer, |cell| cell.vote))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Invitee {
+    pub name: String,
+    pub email: Option<String>,
+    pub responded: bool,
+}
+
+/// Event as seen by the organizer, which includes data that is hidden from participants
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdminEvent {
+    pub event: Event,
+    pub invitees: Vec<Invitee>,
+    pub pick_date_link: EventUrl,
+}
+
+impl AdminEvent {
+    /// Invitees that did not respond yet
+    pub fn pending_invitees(&self) -> impl Iterator<Item = &Invitee> {
+        self.invitees.iter().filter(|invitee| !invitee.responded)
+    }
+
+    /// Names and e-mail addresses of everyone invited to the event
+    pub fn contacts(&self) -> Vec<Contact> {
+        self.invitees
+            .iter()
+            .map(|invitee| Contact {
+                name: invitee.name.clone(),
+                email: invitee.email.clone(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Contact {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{
+        event_url, event_url_at, normalize_event_url, normalize_event_url_at, parse_event_code,
+        DateRange, Event, EventStatus, Vote,
+    };
+    use crate::event_overview_page::parse_page;
+    use crate::event_url::EventUrl;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    #[test]
+    fn event_code() {
+        let text = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let event = parse_page(text).unwrap();
+        assert_eq!(event.code(), "fewqvuycnmvgnx25");
+        assert_eq!(
+            parse_event_code("https://datumprikker.nl/afspraak/overzicht/f4wfumjp7a9ih2nq/"),
+            Some(String::from("f4wfumjp7a9ih2nq"))
+        );
+        // The short link of the event above, which has a code of its own
+        assert_eq!(
+            parse_event_code("https://datumprikker.nl/pux6s6a4febgnx25"),
+            None
+        );
+        assert_eq!(parse_event_code("https://datumprikker.nl/"), None);
+        assert_eq!(parse_event_code("not a url"), None);
+    }
+
+    #[test]
+    fn normalize_url() {
+        let canonical = "https://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25";
+        assert_eq!(
+            normalize_event_url("http://www.datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25/")
+                .map(String::from),
+            Some(String::from(canonical))
+        );
+        assert_eq!(
+            normalize_event_url("https://datumprikker.nl/pux6s6a4febgnx25"),
+            None
+        );
+        assert_eq!(
+            normalize_event_url("https://example.com/afspraak/overzicht/fewqvuycnmvgnx25"),
+            None
+        );
+        assert_eq!(
+            event_url("fewqvuycnmvgnx25").map(String::from),
+            Some(String::from(canonical))
+        );
+        assert_eq!(event_url("../fewqvuycnmvgnx25"), None);
+    }
+
+    #[test]
+    fn custom_base_url() {
+        let base = url::Url::parse("http://localhost:8080/mirror/").unwrap();
+        let canonical = "http://localhost:8080/mirror/afspraak/overzicht/fewqvuycnmvgnx25";
+        assert_eq!(
+            event_url_at(&base, "fewqvuycnmvgnx25").map(String::from),
+            Some(String::from(canonical))
+        );
+        assert_eq!(
+            normalize_event_url_at(
+                &base,
+                "http://localhost:8080/mirror/afspraak/overzicht/fewqvuycnmvgnx25/"
+            )
+            .map(String::from),
+            Some(String::from(canonical))
+        );
+        assert_eq!(
+            normalize_event_url_at(
+                &base,
+                "http://localhost:9090/mirror/afspraak/overzicht/fewqvuycnmvgnx25"
+            ),
+            None
+        );
+        assert_eq!(
+            normalize_event_url_at(
+                &base,
+                "https://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn vote_score_matches_site() {
+        let text = include_str!("../data/afspraak_overzicht_finalized.html");
+        let event = parse_page(text).unwrap();
+        let matrix = event.availability_matrix();
+        let scores: Vec<u32> = (0..matrix.slots.len())
+            .map(|slot| matrix.slot_votes(slot).iter().map(Vote::score).sum())
+            .collect();
+        assert_eq!(scores, vec![350, 300, 350, 350, 350, 300, 100, 200, 100]);
+    }
+
+    #[test]
+    fn availability_matrix() {
+        let text = include_str!("../data/afspraak_overzicht_finalized.html");
+        let matrix = parse_page(text).unwrap().availability_matrix();
+        assert_eq!(
+            matrix.participants,
+            vec!["Casper", "Jeroen", "Mark", "Robin"]
+        );
+        assert_eq!(matrix.slots.len(), 9);
+        assert_eq!(matrix.get(3, 1), Some(Vote::IfNeedBe));
+        assert_eq!(matrix.get(4, 1), None);
+        assert_eq!(
+            matrix.note(3, 1),
+            Some("Moet misschien wat voor mn stiefma doen")
+        );
+        assert_eq!(matrix.note(3, 0), None);
+        assert_eq!(
+            matrix.slot_votes(5),
+            vec![Vote::Yes, Vote::Yes, Vote::No, Vote::Yes]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let text = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let event = parse_page(text).unwrap();
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(serde_json::from_str::<crate::Event>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn builder() {
+        let start = Utc.with_ymd_and_hms(2022, 6, 3, 18, 0, 0).unwrap();
+        let date = DateRange { start, end: start };
+        let url = EventUrl::parse("https://datumprikker.nl/afspraak/overzicht/abc").unwrap();
+        let event = Event::builder(url)
+            .title("Game night")
+            .final_date(date.clone())
+            .build();
+        assert_eq!(event.title, "Game night");
+        assert_eq!(event.code(), "abc");
+        assert_eq!(event.final_dates, vec![date]);
+        assert_eq!(event.status, EventStatus::Finalized);
+        assert!(event.participants.is_empty());
+    }
+
+    #[test]
+    fn date_range() {
+        let at = |day, hour| Utc.with_ymd_and_hms(2022, 6, day, hour, 0, 0).unwrap();
+        let range = |start, end| DateRange { start, end };
+        let evening = range(at(3, 18), at(3, 21));
+        let night = range(at(3, 21), at(4, 1));
+        let moment = range(at(3, 19), at(3, 19));
+
+        assert_eq!(evening.duration(), chrono::Duration::hours(3));
+        assert_eq!(moment.duration(), chrono::Duration::zero());
+
+        assert!(evening.contains(&at(3, 18)));
+        assert!(!evening.contains(&at(3, 21)));
+        assert!(moment.contains(&at(3, 19)));
+
+        assert!(!evening.overlaps(&night));
+        assert!(evening.overlaps(&moment));
+        assert!(moment.overlaps(&evening));
+        assert!(!moment.overlaps(&night));
+    }
 }
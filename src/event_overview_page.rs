@@ -15,13 +15,14 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use crate::event::DateRange;
+use crate::event::{DateRange, EventStatus, Language, Organizer, Participant, Slot, Vote};
+use crate::event_url::EventUrl;
 use crate::Event;
 use chrono::DateTime;
 use chrono::Utc;
 use thiserror::Error;
 
-#[derive(Error, Debug, Eq, PartialEq)]
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
 pub enum ParsePageError {
     #[error("the requested event is non-existing")]
     NonExistingEvent,
@@ -29,21 +30,134 @@ pub enum ParsePageError {
     UnexpectedHtml,
     #[error("parsed html has a date in an unexpected format")]
     DateParseError,
+    #[error("the page is not the organizer view of the event")]
+    NotOrganizerView,
+    #[error("the event is a poll of type {0}, only date polls are supported")]
+    UnsupportedEventType(String),
+    #[error("the site shows its maintenance page")]
+    Maintenance,
 }
 
+/// Field that failed to parse during lenient parsing
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+#[error("failed to parse the {field} of the event")]
+pub struct FieldError {
+    /// Name of the field of [`Event`] that is left empty, e.g. `title`
+    pub field: &'static str,
+    #[source]
+    pub error: ParsePageError,
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err)
+)]
 pub fn parse_page(text: &str) -> Result<Event, ParsePageError> {
     let document = select::document::Document::from(text);
+    parse_document(&document)
+}
+
+/// Parses as much of the page as possible; fields that fail to parse are left empty and the
+/// corresponding errors, named after the field, are returned alongside the event
+///
+/// The canonical URL identifies the event, so failing to parse it is still an error.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err)
+)]
+pub fn parse_page_lenient(text: &str) -> Result<(Event, Vec<FieldError>), ParsePageError> {
+    let document = select::document::Document::from(text);
+    parse_document_lenient(&document)
+}
 
-    let page_id = parse_page_id(&document)?;
+pub(crate) fn parse_document(
+    document: &select::document::Document,
+) -> Result<Event, ParsePageError> {
+    let (event, errors) = parse_document_lenient(document)?;
+    match errors.into_iter().next() {
+        Some(error) => Err(error.error),
+        None => Ok(event),
+    }
+}
+
+fn parse_document_lenient(
+    document: &select::document::Document,
+) -> Result<(Event, Vec<FieldError>), ParsePageError> {
+    let page_id = parse_page_id(document).map_err(|error| {
+        if is_maintenance_page(document) {
+            ParsePageError::Maintenance
+        } else {
+            error
+        }
+    })?;
     if page_id == "page_home_index" {
         return Err(ParsePageError::NonExistingEvent);
     }
 
-    Ok(Event {
-        canonical_url: parse_canonical_url(&document)?,
-        title: parse_page_title(&document)?,
-        final_date: parse_page_final_date(&document)?,
-        open_registration_link: parse_page_open_registration_link(&document)?,
+    let mut errors = vec![];
+    // Only a type that is known to differ rejects the page; a missing type is a markup change
+    match parse_page_event_type(document) {
+        Ok(event_type) if event_type != "Appointment" => {
+            return Err(ParsePageError::UnsupportedEventType(event_type));
+        }
+        Ok(_) => {}
+        Err(error) => errors.push(FieldError {
+            field: "event_type",
+            error,
+        }),
+    }
+
+    let language = parse_page_language(document);
+
+    let description = or_default("description", parse_page_description(document), &mut errors);
+    let event = Event {
+        canonical_url: parse_canonical_url(document)?,
+        title: or_default("title", parse_page_title(document), &mut errors),
+        final_dates: or_default("final_dates", parse_page_final_dates(document), &mut errors),
+        open_registration_link: or_default(
+            "open_registration_link",
+            parse_page_open_registration_link(document),
+            &mut errors,
+        ),
+        participants: or_default(
+            "participants",
+            parse_page_participants(document),
+            &mut errors,
+        ),
+        proposed_dates: or_default(
+            "proposed_dates",
+            parse_page_proposed_dates(document),
+            &mut errors,
+        ),
+        site_suggested_date: or_default(
+            "site_suggested_date",
+            parse_page_site_suggested_date(document),
+            &mut errors,
+        ),
+        links: description.as_deref().map(find_links).unwrap_or_default(),
+        description,
+        organizer: parse_page_organizer(document),
+        location: parse_page_location(document),
+        status: parse_page_status(document),
+        language,
+        participant_count: or_default(
+            "participant_count",
+            parse_page_participant_count(document),
+            &mut errors,
+        ),
+        timezone: parse_page_timezone(document),
+    };
+    Ok((event, errors))
+}
+
+fn or_default<T: Default>(
+    field: &'static str,
+    result: Result<T, ParsePageError>,
+    errors: &mut Vec<FieldError>,
+) -> T {
+    result.unwrap_or_else(|error| {
+        errors.push(FieldError { field, error });
+        T::default()
     })
 }
 
@@ -57,8 +171,20 @@ fn parse_page_id(document: &select::document::Document) -> Result<String, ParseP
         .to_string())
 }
 
-fn parse_canonical_url(document: &select::document::Document) -> Result<String, ParsePageError> {
-    Ok(document
+/// No maintenance page has been captured, so it is recognized by a title or heading that mentions
+/// maintenance, on a page without the page id that every page of the site has
+fn is_maintenance_page(document: &select::document::Document) -> bool {
+    document
+        .find(select::predicate::Or(
+            select::predicate::Name("title"),
+            select::predicate::Or(select::predicate::Name("h1"), select::predicate::Name("h2")),
+        ))
+        .map(|node| node.text().to_lowercase())
+        .any(|text| text.contains("onderhoud") || text.contains("maintenance"))
+}
+
+fn parse_canonical_url(document: &select::document::Document) -> Result<EventUrl, ParsePageError> {
+    let href = document
         .find(select::predicate::And(
             select::predicate::Name("link"),
             select::predicate::Attr("rel", "canonical"),
@@ -66,8 +192,16 @@ fn parse_canonical_url(document: &select::document::Document) -> Result<String,
         .next()
         .ok_or(ParsePageError::UnexpectedHtml)?
         .attr("href")
-        .ok_or(ParsePageError::UnexpectedHtml)?
-        .to_string())
+        .ok_or(ParsePageError::UnexpectedHtml)?;
+    parse_page_url(href)
+}
+
+/// Links on the page may point to a mirror, so their host isn't checked
+pub(crate) fn parse_page_url(href: &str) -> Result<EventUrl, ParsePageError> {
+    url::Url::parse(href)
+        .ok()
+        .and_then(|url| EventUrl::try_from(url).ok())
+        .ok_or(ParsePageError::UnexpectedHtml)
 }
 
 fn parse_page_title(document: &select::document::Document) -> Result<String, ParsePageError> {
@@ -80,59 +214,332 @@ fn parse_page_title(document: &select::document::Document) -> Result<String, Par
         .to_string())
 }
 
-fn parse_page_final_date(
+fn parse_page_event_type(document: &select::document::Document) -> Result<String, ParsePageError> {
+    Ok(document
+        .find(select::predicate::Name("article"))
+        .next()
+        .ok_or(ParsePageError::UnexpectedHtml)?
+        .attr("data-event-type")
+        .ok_or(ParsePageError::UnexpectedHtml)?
+        .to_string())
+}
+
+fn parse_page_final_dates(
     document: &select::document::Document,
-) -> Result<Option<DateRange>, ParsePageError> {
+) -> Result<Vec<DateRange>, ParsePageError> {
     if let Some(final_summary) = document
         .find(select::predicate::Attr("id", "final_summary"))
         .next()
     {
-        let final_date = final_summary
+        let final_dates: Vec<DateRange> = final_summary
             .find(select::predicate::Class("date"))
-            .next()
-            .ok_or(ParsePageError::UnexpectedHtml)?;
-
-        let start_text = final_date
-            .attr("data-startdate")
-            .ok_or(ParsePageError::UnexpectedHtml)?;
-        let end_text = final_date
-            .attr("data-enddate")
-            .ok_or(ParsePageError::UnexpectedHtml)?;
-
-        Ok(Some(DateRange {
-            start: DateTime::parse_from_rfc3339(start_text)
-                .map_err(|_err| ParsePageError::DateParseError)?
-                .with_timezone(&Utc),
-            end: DateTime::parse_from_rfc3339(end_text)
-                .map_err(|_err| ParsePageError::DateParseError)?
-                .with_timezone(&Utc),
-        }))
+            .map(|final_date| parse_date_range(&final_date))
+            .collect::<Result<_, _>>()?;
+
+        if final_dates.is_empty() {
+            return Err(ParsePageError::UnexpectedHtml);
+        }
+        Ok(final_dates)
     } else {
-        Ok(None)
+        Ok(vec![])
+    }
+}
+
+fn parse_page_language(document: &select::document::Document) -> Option<Language> {
+    let lang = document
+        .find(select::predicate::Name("html"))
+        .next()?
+        .attr("lang")?;
+    match lang.split('-').next()? {
+        "nl" => Some(Language::Dutch),
+        "en" => Some(Language::English),
+        _ => None,
     }
 }
 
+/// The final date in the past is only marked by the text of a heading, without a class or
+/// attribute. That text is only known in Dutch, so other languages always report `Finalized`.
+fn parse_page_status(document: &select::document::Document) -> EventStatus {
+    if let Some(final_summary) = document
+        .find(select::predicate::Attr("id", "final_summary"))
+        .next()
+    {
+        let in_past = final_summary
+            .find(select::predicate::Name("h3"))
+            .any(|heading| heading.text().trim() == "Ligt in het verleden");
+        if in_past {
+            EventStatus::Expired
+        } else {
+            EventStatus::Finalized
+        }
+    } else {
+        EventStatus::Open
+    }
+}
+
+fn parse_page_proposed_dates(
+    document: &select::document::Document,
+) -> Result<Vec<Slot>, ParsePageError> {
+    find_event_dates(document)
+        .map(|event_date| {
+            let date = event_date
+                .find(select::predicate::Class("date"))
+                .next()
+                .ok_or(ParsePageError::UnexpectedHtml)?;
+            Ok(Slot {
+                range: parse_date_range(&date)?,
+                yes: parse_score(&event_date, "yes")?,
+                maybe: parse_score(&event_date, "maybe")?,
+                no: parse_score(&event_date, "no")?,
+            })
+        })
+        .collect()
+}
+
+fn parse_page_site_suggested_date(
+    document: &select::document::Document,
+) -> Result<Option<DateRange>, ParsePageError> {
+    let mut best: Option<(u32, DateRange)> = None;
+    for event_date in find_event_dates(document) {
+        let score = event_date
+            .attr("data-score")
+            .ok_or(ParsePageError::UnexpectedHtml)?
+            .parse()
+            .map_err(|_err| ParsePageError::UnexpectedHtml)?;
+        if !matches!(&best, Some((best_score, _)) if *best_score >= score) {
+            let date = event_date
+                .find(select::predicate::Class("date"))
+                .next()
+                .ok_or(ParsePageError::UnexpectedHtml)?;
+            best = Some((score, parse_date_range(&date)?));
+        }
+    }
+    Ok(best.map(|(_, range)| range))
+}
+
+fn parse_score(event_date: &select::node::Node, class: &str) -> Result<u32, ParsePageError> {
+    event_date
+        .find(select::predicate::Descendant(
+            select::predicate::Class("scores"),
+            select::predicate::Class(class),
+        ))
+        .next()
+        .ok_or(ParsePageError::UnexpectedHtml)?
+        .text()
+        .trim()
+        .parse()
+        .map_err(|_err| ParsePageError::UnexpectedHtml)
+}
+
+fn find_event_dates(
+    document: &select::document::Document,
+) -> impl Iterator<Item = select::node::Node<'_>> {
+    document.find(select::predicate::Descendant(
+        select::predicate::Class("evd_details"),
+        select::predicate::Class("eventdate"),
+    ))
+}
+
+fn parse_date_range(date: &select::node::Node) -> Result<DateRange, ParsePageError> {
+    let start_text = date
+        .attr("data-startdate")
+        .ok_or(ParsePageError::UnexpectedHtml)?;
+    let end_text = date
+        .attr("data-enddate")
+        .ok_or(ParsePageError::UnexpectedHtml)?;
+
+    let start = parse_date_time(start_text)?;
+    let end = if end_text.is_empty() {
+        start
+    } else {
+        parse_date_time(end_text)?
+    };
+    Ok(DateRange { start, end })
+}
+
+fn parse_date_time(text: &str) -> Result<DateTime<Utc>, ParsePageError> {
+    Ok(DateTime::parse_from_rfc3339(text)
+        .map_err(|_err| ParsePageError::DateParseError)?
+        .with_timezone(&Utc))
+}
+
 fn parse_page_open_registration_link(
     document: &select::document::Document,
-) -> Result<Option<String>, ParsePageError> {
+) -> Result<Option<EventUrl>, ParsePageError> {
     let link = document
         .find(select::predicate::Name("article"))
         .next()
         .ok_or(ParsePageError::UnexpectedHtml)?
         .attr("data-openregistration-link")
+        .ok_or(ParsePageError::UnexpectedHtml)?;
+    if link.is_empty() {
+        Ok(None)
+    } else {
+        parse_page_url(link).map(Some)
+    }
+}
+
+fn parse_page_description(
+    document: &select::document::Document,
+) -> Result<Option<String>, ParsePageError> {
+    let description = document
+        .find(select::predicate::Name("article"))
+        .next()
+        .ok_or(ParsePageError::UnexpectedHtml)?
+        .attr("data-event-description")
         .ok_or(ParsePageError::UnexpectedHtml)?
         .to_string();
-    if link.is_empty() {
+    if description.is_empty() {
         Ok(None)
     } else {
-        Ok(Some(link))
+        Ok(Some(description))
     }
 }
 
+fn find_links(text: &str) -> Vec<url::Url> {
+    text.split_whitespace()
+        .map(|word| {
+            word.trim_start_matches(|c| "([<\"'".contains(c))
+                .trim_end_matches(|c| ".,;:!?)]>\"'".contains(c))
+        })
+        .filter(|word| word.starts_with("http://") || word.starts_with("https://"))
+        .filter_map(|word| url::Url::parse(word).ok())
+        .collect()
+}
+
+fn parse_page_timezone(document: &select::document::Document) -> Option<chrono_tz::Tz> {
+    let name = document
+        .find(select::predicate::Name("article"))
+        .next()?
+        .attr("data-event-tz")?;
+    crate::windows_timezone::parse_windows_timezone(name)
+}
+
+fn parse_page_organizer(document: &select::document::Document) -> Option<Organizer> {
+    let organizer = document
+        .find(select::predicate::Descendant(
+            select::predicate::Class("event_meta"),
+            select::predicate::Class("organizer"),
+        ))
+        .next()?;
+    let email = organizer.attr("data-email").unwrap_or_default();
+
+    Some(Organizer {
+        name: organizer.text().trim().to_string(),
+        email: if email.is_empty() {
+            None
+        } else {
+            Some(email.to_string())
+        },
+    })
+}
+
+/// The location is only known from the "add to calendar" links, which are shown once a date is
+/// picked
+fn parse_page_location(document: &select::document::Document) -> Option<String> {
+    document
+        .find(select::predicate::Descendant(
+            select::predicate::Class("eventcalender"),
+            select::predicate::Name("a"),
+        ))
+        .filter_map(|link| url::Url::parse(link.attr("href")?).ok())
+        .find_map(|link| {
+            link.query_pairs()
+                .find(|(key, _value)| key == "location")
+                .map(|(_key, value)| value.trim().to_string())
+        })
+        .filter(|location| !location.is_empty())
+}
+
+fn parse_page_participant_count(
+    document: &select::document::Document,
+) -> Result<Option<u32>, ParsePageError> {
+    document
+        .find(select::predicate::Name("article"))
+        .next()
+        .ok_or(ParsePageError::UnexpectedHtml)?
+        .attr("data-responsecount")
+        .map(|count| count.parse().map_err(|_err| ParsePageError::UnexpectedHtml))
+        .transpose()
+}
+
+fn parse_page_participants(
+    document: &select::document::Document,
+) -> Result<Vec<Participant>, ParsePageError> {
+    let event_dates: Vec<_> = find_event_dates(document).collect();
+
+    document
+        .find(select::predicate::Descendant(
+            select::predicate::Attr("id", "matrix-participants"),
+            select::predicate::Name("li"),
+        ))
+        .map(|participant| {
+            let id = participant
+                .attr("data-id")
+                .ok_or(ParsePageError::UnexpectedHtml)?;
+            let name = participant
+                .find(select::predicate::Name("span"))
+                .next()
+                .ok_or(ParsePageError::UnexpectedHtml)?;
+            let email = name.attr("title").unwrap_or_default();
+
+            Ok(Participant {
+                name: name.text().trim().to_string(),
+                email: if email.is_empty() {
+                    None
+                } else {
+                    Some(email.to_string())
+                },
+                availability: event_dates
+                    .iter()
+                    .map(|event_date| parse_vote(event_date, id))
+                    .collect(),
+                notes: event_dates
+                    .iter()
+                    .map(|event_date| parse_note(event_date, id))
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn parse_vote(event_date: &select::node::Node, participant_id: &str) -> Vote {
+    [
+        ("responses_yes", Vote::Yes),
+        ("responses_maybe", Vote::IfNeedBe),
+        ("responses_no", Vote::No),
+    ]
+    .into_iter()
+    .find(|(class, _vote)| {
+        event_date
+            .find(select::predicate::Descendant(
+                select::predicate::Class(*class),
+                select::predicate::Attr("data-id", participant_id),
+            ))
+            .next()
+            .is_some()
+    })
+    .map(|(_class, vote)| vote)
+    .unwrap_or(Vote::NoAnswer)
+}
+
+fn parse_note(event_date: &select::node::Node, participant_id: &str) -> Option<String> {
+    let note = event_date
+        .find(select::predicate::Descendant(
+            select::predicate::Attr("data-id", participant_id),
+            select::predicate::Class("response_comment"),
+        ))
+        .next()?;
+    Some(note.text().trim().to_string())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::event::DateRange;
-    use crate::event_overview_page::{parse_page, ParsePageError};
+    use crate::event::{DateRange, EventStatus, Language, Organizer, Participant, Slot, Vote};
+    use crate::event_overview_page::{
+        find_links, parse_page, parse_page_lenient, FieldError, ParsePageError,
+    };
+    use crate::event_url::EventUrl;
     use crate::Event;
     use chrono::{TimeZone, Utc};
 
@@ -149,14 +556,133 @@ mod tests {
         assert_eq!(
             event,
             Event {
-                canonical_url: String::from(
+                canonical_url: EventUrl::parse(
                     "http://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25"
-                ),
+                )
+                .unwrap(),
                 title: String::from("D&D Avernus week 29"),
-                final_date: None,
-                open_registration_link: Some(String::from(
-                    "https://datumprikker.nl/pux6s6a4febgnx25"
+                final_dates: vec![],
+                open_registration_link: Some(
+                    EventUrl::parse("https://datumprikker.nl/pux6s6a4febgnx25").unwrap()
+                ),
+                participants: vec![Participant {
+                    name: String::from("Casper"),
+                    email: Some(String::from("casper@meijn.net")),
+                    availability: vec![
+                        Vote::Yes,
+                        Vote::Yes,
+                        Vote::Yes,
+                        Vote::Yes,
+                        Vote::Yes,
+                        Vote::Yes,
+                        Vote::Yes,
+                        Vote::Yes,
+                        Vote::Yes
+                    ],
+                    notes: vec![None, None, None, None, None, None, None, None, None],
+                }],
+                proposed_dates: vec![
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 18).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 7, 18).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 19).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 7, 19).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 20).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 7, 20).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 21).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 7, 21).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 22).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 7, 22).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 23).and_hms(11, 0, 0),
+                            end: Utc.ymd(2022, 7, 23).and_hms(15, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 23).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 7, 23).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 24).and_hms(11, 0, 0),
+                            end: Utc.ymd(2022, 7, 24).and_hms(15, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 7, 24).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 7, 24).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 0,
+                    },
+                ],
+                description: Some(String::from(
+                    "Graag aanwezig aangeven voor dagen met voorkeur. \
+                     Graag misschien aangeven voor dagen die in principe kunnen, \
+                     maar niet de voorkeur hebben."
                 )),
+                organizer: Some(Organizer {
+                    name: String::from("Casper"),
+                    email: Some(String::from("casper@meijn.net")),
+                }),
+                location: None,
+                status: EventStatus::Open,
+                language: Some(Language::Dutch),
+                participant_count: Some(1),
+                links: vec![],
+                timezone: Some(chrono_tz::Europe::Berlin),
+                site_suggested_date: Some(DateRange {
+                    start: Utc.ymd(2022, 7, 18).and_hms(17, 0, 0),
+                    end: Utc.ymd(2022, 7, 18).and_hms(21, 0, 0),
+                }),
             }
         )
     }
@@ -168,17 +694,196 @@ mod tests {
         assert_eq!(
             event,
             Event {
-                canonical_url: String::from(
+                canonical_url: EventUrl::parse(
                     "http://datumprikker.nl/afspraak/overzicht/f4wfumjp7a9ih2nq"
-                ),
+                )
+                .unwrap(),
                 title: String::from("D&D Avernus Week 22"),
-                final_date: Some(DateRange {
+                final_dates: vec![DateRange {
                     start: Utc.ymd(2022, 6, 3).and_hms(17, 0, 0),
                     end: Utc.ymd(2022, 6, 3).and_hms(21, 0, 0),
-                }),
-                open_registration_link: Some(String::from(
-                    "https://datumprikker.nl/pbxzxuf7c8sih2nq"
+                }],
+                open_registration_link: Some(
+                    EventUrl::parse("https://datumprikker.nl/pbxzxuf7c8sih2nq").unwrap()
+                ),
+                participants: vec![
+                    Participant {
+                        name: String::from("Casper"),
+                        email: Some(String::from("casper@meijn.net")),
+                        availability: vec![
+                            Vote::IfNeedBe,
+                            Vote::IfNeedBe,
+                            Vote::IfNeedBe,
+                            Vote::IfNeedBe,
+                            Vote::IfNeedBe,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::No
+                        ],
+                        notes: vec![None, None, None, None, None, None, None, None, None],
+                    },
+                    Participant {
+                        name: String::from("Jeroen"),
+                        email: Some(String::from("jeroengruijs@hotmail.com")),
+                        availability: vec![
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::No,
+                            Vote::No,
+                            Vote::No
+                        ],
+                        notes: vec![None, None, None, None, None, None, None, None, None],
+                    },
+                    Participant {
+                        name: String::from("Mark"),
+                        email: Some(String::from("mark.bruin@hotmail.com")),
+                        availability: vec![
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::No,
+                            Vote::No,
+                            Vote::No,
+                            Vote::No
+                        ],
+                        notes: vec![None, None, None, None, None, None, None, None, None],
+                    },
+                    Participant {
+                        name: String::from("Robin"),
+                        email: Some(String::from("de.gier.robin@gmail.com")),
+                        availability: vec![
+                            Vote::Yes,
+                            Vote::IfNeedBe,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::Yes,
+                            Vote::No,
+                            Vote::Yes,
+                            Vote::Yes
+                        ],
+                        notes: vec![
+                            None,
+                            Some(String::from("Moet misschien wat voor mn stiefma doen")),
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None
+                        ],
+                    },
+                ],
+                proposed_dates: vec![
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 5, 30).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 5, 30).and_hms(21, 0, 0),
+                        },
+                        yes: 3,
+                        maybe: 1,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 5, 31).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 5, 31).and_hms(21, 0, 0),
+                        },
+                        yes: 2,
+                        maybe: 2,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 6, 1).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 6, 1).and_hms(21, 0, 0),
+                        },
+                        yes: 3,
+                        maybe: 1,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 6, 2).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 6, 2).and_hms(21, 0, 0),
+                        },
+                        yes: 3,
+                        maybe: 1,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 6, 3).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 6, 3).and_hms(21, 0, 0),
+                        },
+                        yes: 3,
+                        maybe: 1,
+                        no: 0,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 6, 4).and_hms(11, 0, 0),
+                            end: Utc.ymd(2022, 6, 4).and_hms(15, 0, 0),
+                        },
+                        yes: 3,
+                        maybe: 0,
+                        no: 1,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 6, 4).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 6, 4).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 3,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 6, 5).and_hms(11, 0, 0),
+                            end: Utc.ymd(2022, 6, 5).and_hms(15, 0, 0),
+                        },
+                        yes: 2,
+                        maybe: 0,
+                        no: 2,
+                    },
+                    Slot {
+                        range: DateRange {
+                            start: Utc.ymd(2022, 6, 5).and_hms(17, 0, 0),
+                            end: Utc.ymd(2022, 6, 5).and_hms(21, 0, 0),
+                        },
+                        yes: 1,
+                        maybe: 0,
+                        no: 3,
+                    },
+                ],
+                description: Some(String::from(
+                    "Graag aanwezig aangeven voor dagen met voorkeur. \
+                     Graag misschien aangeven voor dagen die in principe kunnen, \
+                     maar niet de voorkeur hebben."
                 )),
+                organizer: Some(Organizer {
+                    name: String::from("Casper"),
+                    email: Some(String::from("casper@meijn.net")),
+                }),
+                location: None,
+                status: EventStatus::Expired,
+                language: Some(Language::Dutch),
+                participant_count: Some(4),
+                links: vec![],
+                timezone: Some(chrono_tz::Europe::Berlin),
+                site_suggested_date: Some(DateRange {
+                    start: Utc.ymd(2022, 5, 30).and_hms(17, 0, 0),
+                    end: Utc.ymd(2022, 5, 30).and_hms(21, 0, 0),
+                }),
             }
         )
     }
@@ -190,20 +895,189 @@ mod tests {
         assert_eq!(
             event,
             Event {
-                canonical_url: String::from(
+                canonical_url: EventUrl::parse(
                     "http://datumprikker.nl/afspraak/overzicht/mu2edbyv3bfayubtm"
-                ),
+                )
+                .unwrap(),
                 title: String::from("test"),
-                final_date: None,
+                final_dates: vec![],
                 open_registration_link: None,
+                participants: vec![
+                    Participant {
+                        name: String::from("Casper"),
+                        email: None,
+                        availability: vec![Vote::Yes],
+                        notes: vec![None],
+                    },
+                    Participant {
+                        name: String::from("dfsg"),
+                        email: None,
+                        availability: vec![Vote::Yes],
+                        notes: vec![None],
+                    },
+                ],
+                proposed_dates: vec![Slot {
+                    range: DateRange {
+                        start: Utc.ymd(2022, 8, 10).and_hms(14, 0, 0),
+                        end: Utc.ymd(2022, 8, 10).and_hms(14, 0, 0),
+                    },
+                    yes: 2,
+                    maybe: 0,
+                    no: 0,
+                },],
+                description: None,
+                organizer: Some(Organizer {
+                    name: String::from("Casper"),
+                    email: Some(String::from("casper@meijn.net")),
+                }),
+                location: None,
+                status: EventStatus::Open,
+                language: Some(Language::Dutch),
+                participant_count: Some(2),
+                links: vec![],
+                timezone: Some(chrono_tz::Europe::Berlin),
+                site_suggested_date: Some(DateRange {
+                    start: Utc.ymd(2022, 8, 10).and_hms(14, 0, 0),
+                    end: Utc.ymd(2022, 8, 10).and_hms(14, 0, 0),
+                }),
             }
         )
     }
 
+    #[test]
+    fn event_with_location() {
+        let text = include_str!("../data/afspraak_overzicht_finalized.html").replace(
+            "&amp;location=&amp;sf=true",
+            "&amp;location=De+Drie+Draken%2c+Utrecht&amp;sf=true",
+        );
+        let event = parse_page(&text).unwrap();
+        assert_eq!(
+            event.location,
+            Some(String::from("De Drie Draken, Utrecht"))
+        );
+    }
+
+    #[test]
+    fn maintenance_page() {
+        let text = "<html><head><title>Datumprikker - Onderhoud</title></head>\
+                    <body><h1>We zijn zo terug</h1></body></html>";
+        assert_eq!(parse_page(text), Err(ParsePageError::Maintenance));
+        let text = "<html><body><h1>Something else</h1></body></html>";
+        assert_eq!(parse_page(text), Err(ParsePageError::UnexpectedHtml));
+    }
+
     #[test]
     fn invalid_event() {
         let text = include_str!("../data/afspraak_overzicht_invalid.html");
         let event = parse_page(text);
         assert_eq!(event, Err(ParsePageError::NonExistingEvent))
     }
+
+    #[test]
+    fn unsupported_event_type() {
+        let text = include_str!("../data/afspraak_overzicht_in_progress.html").replace(
+            "data-event-type=\"Appointment\"",
+            "data-event-type=\"Survey\"",
+        );
+        let expected = Err(ParsePageError::UnsupportedEventType(String::from("Survey")));
+        assert_eq!(parse_page(&text), expected);
+        assert_eq!(parse_page_lenient(&text).map(|(event, _)| event), expected);
+    }
+
+    #[test]
+    fn lenient_missing_event_type() {
+        let text = include_str!("../data/afspraak_overzicht_in_progress.html")
+            .replace("data-event-type=", "data-unknown=");
+        let (event, errors) = parse_page_lenient(&text).unwrap();
+        assert_eq!(event.title, "D&D Avernus week 29");
+        assert_eq!(
+            errors,
+            vec![FieldError {
+                field: "event_type",
+                error: ParsePageError::UnexpectedHtml,
+            }]
+        );
+    }
+
+    #[test]
+    fn lenient_valid_event() {
+        let text = include_str!("../data/afspraak_overzicht_finalized.html");
+        let (event, errors) = parse_page_lenient(text).unwrap();
+        assert_eq!(event, parse_page(text).unwrap());
+        assert_eq!(errors, vec![]);
+    }
+
+    #[test]
+    fn lenient_missing_title() {
+        let text = include_str!("../data/afspraak_overzicht_finalized.html")
+            .replace("data-event-title=", "data-unknown=");
+        let (event, errors) = parse_page_lenient(&text).unwrap();
+        assert_eq!(event.title, "");
+        assert_eq!(event.participants.len(), 4);
+        assert_eq!(
+            errors,
+            vec![FieldError {
+                field: "title",
+                error: ParsePageError::UnexpectedHtml,
+            }]
+        );
+        assert_eq!(parse_page(&text), Err(ParsePageError::UnexpectedHtml));
+    }
+
+    #[test]
+    fn lenient_invalid_event() {
+        let text = include_str!("../data/afspraak_overzicht_invalid.html");
+        let event = parse_page_lenient(text);
+        assert_eq!(event, Err(ParsePageError::NonExistingEvent))
+    }
+
+    #[test]
+    fn english_event() {
+        let text = include_str!("../data/afspraak_overzicht_in_progress.html")
+            .replace("<html lang=\"nl\"", "<html lang=\"en\"");
+        let event = parse_page(&text).unwrap();
+        assert_eq!(event.language, Some(Language::English));
+        assert_eq!(event.title, "D&D Avernus week 29");
+        assert_eq!(event.proposed_dates.len(), 9);
+    }
+
+    #[test]
+    fn expired_event_without_language() {
+        let text = include_str!("../data/afspraak_overzicht_finalized.html")
+            .replace("<html lang=\"nl\"", "<html");
+        let event = parse_page(&text).unwrap();
+        assert_eq!(event.language, None);
+        assert_eq!(event.status, EventStatus::Expired);
+    }
+
+    #[test]
+    fn links_in_description() {
+        let links = find_links(
+            "Join via https://meet.jit.si/avernus, the map is at \
+             (https://example.com/map?level=2). Not a link: datumprikker.nl",
+        );
+        assert_eq!(
+            links,
+            vec![
+                url::Url::parse("https://meet.jit.si/avernus").unwrap(),
+                url::Url::parse("https://example.com/map?level=2").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn mirrored_event() {
+        let text = include_str!("../data/afspraak_overzicht_in_progress.html")
+            .replace("http://datumprikker.nl/", "http://localhost:8080/")
+            .replace("https://datumprikker.nl/", "http://localhost:8080/");
+        let event = parse_page(&text).unwrap();
+        assert_eq!(
+            event.canonical_url,
+            "http://localhost:8080/afspraak/overzicht/fewqvuycnmvgnx25"
+        );
+        assert_eq!(
+            event.open_registration_link.unwrap(),
+            "http://localhost:8080/pux6s6a4febgnx25"
+        );
+    }
 }
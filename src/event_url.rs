@@ -0,0 +1,255 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum ParseEventUrlError {
+    #[error("invalid URL")]
+    InvalidUrl(#[from] url::ParseError),
+    #[error("URL points to another site than datumprikker.nl")]
+    UnknownHost,
+    #[error("URL does not point to a datumprikker event")]
+    NotAnEvent,
+}
+
+/// Link to an event on datumprikker.nl
+///
+/// Overview URLs (`/afspraak/overzicht/<code>`), pick-date URLs (`/afspraak/selecteer/<code>`)
+/// and short links (`/<code>`) are accepted. A short link needs a code shaped like the ones the
+/// site hands out, so pages like `/afspraak` or `/account` are not taken for an event.
+///
+/// Parsing a string checks that the link points to the site. Converting from a [`url::Url`]
+/// accepts any host instead, because links read from a page or from storage may point to a
+/// mirror; this is also how deserialization works.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "url::Url", into = "String")
+)]
+pub struct EventUrl {
+    url: url::Url,
+    code: String,
+}
+
+impl EventUrl {
+    pub fn parse(url: &str) -> Result<Self, ParseEventUrlError> {
+        Self::parse_at(&crate::event::default_base_url(), url)
+    }
+
+    /// Like [`EventUrl::parse`], for the site hosted at `base`, e.g. a mirror or a test server
+    pub fn parse_at(base: &url::Url, url: &str) -> Result<Self, ParseEventUrlError> {
+        let url = url::Url::parse(url)?;
+        let path = crate::event::site_path(base, &url).ok_or(ParseEventUrlError::UnknownHost)?;
+        let segments = segments(path);
+        let code = code_in(&segments).ok_or(ParseEventUrlError::NotAnEvent)?;
+        Self::new(code.to_string(), url)
+    }
+
+    fn new(code: String, url: url::Url) -> Result<Self, ParseEventUrlError> {
+        if !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(ParseEventUrlError::NotAnEvent);
+        }
+        Ok(EventUrl { url, code })
+    }
+
+    /// Code in the link, e.g. `fewqvuycnmvgnx25`
+    ///
+    /// Short links have a code of their own, which differs from the code of the overview page.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub fn url(&self) -> &url::Url {
+        &self.url
+    }
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+fn code_in<'a>(segments: &[&'a str]) -> Option<&'a str> {
+    match segments {
+        ["afspraak", "overzicht" | "selecteer", code] => Some(code),
+        [code] if is_short_link_code(code) => Some(code),
+        _ => None,
+    }
+}
+
+/// Top-level pages of the site, which are never a short link
+const SITE_PAGES: &[&str] = &[
+    "account",
+    "adresboek",
+    "afspraak",
+    "afspraken",
+    "algemeen",
+    "assets",
+    "blog",
+    "contact",
+    "content",
+    "event",
+    "login",
+    "over",
+    "plus",
+    "site",
+];
+
+/// Codes handed out by the site are 16 or 17 lowercase letters and digits, e.g.
+/// `pbxzxuf7c8sih2nq`
+fn is_short_link_code(code: &str) -> bool {
+    (16..=17).contains(&code.len())
+        && code
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && code.chars().any(|c| c.is_ascii_digit())
+        && !SITE_PAGES.contains(&code)
+}
+
+impl TryFrom<url::Url> for EventUrl {
+    type Error = ParseEventUrlError;
+
+    /// Accepts any host; on a mirror, the site may be hosted below a path
+    fn try_from(url: url::Url) -> Result<Self, Self::Error> {
+        let segments = segments(url.path());
+        let code = code_in(&segments)
+            .or_else(|| code_in(&segments[segments.len().saturating_sub(3)..]))
+            .ok_or(ParseEventUrlError::NotAnEvent)?
+            .to_string();
+        Self::new(code, url)
+    }
+}
+
+impl fmt::Display for EventUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.url.fmt(f)
+    }
+}
+
+impl AsRef<str> for EventUrl {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::str::FromStr for EventUrl {
+    type Err = ParseEventUrlError;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        Self::parse(url)
+    }
+}
+
+impl From<EventUrl> for String {
+    fn from(url: EventUrl) -> Self {
+        url.url.into()
+    }
+}
+
+impl PartialEq<str> for EventUrl {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for EventUrl {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event_url::{EventUrl, ParseEventUrlError};
+
+    #[test]
+    fn parse() {
+        let url =
+            EventUrl::parse("http://datumprikker.nl/afspraak/overzicht/f4wfumjp7a9ih2nq").unwrap();
+        assert_eq!(url.code(), "f4wfumjp7a9ih2nq");
+        assert_eq!(
+            url,
+            "http://datumprikker.nl/afspraak/overzicht/f4wfumjp7a9ih2nq"
+        );
+
+        let url = EventUrl::parse("https://www.datumprikker.nl/pbxzxuf7c8sih2nq").unwrap();
+        assert_eq!(url.code(), "pbxzxuf7c8sih2nq");
+        let url = EventUrl::parse("https://datumprikker.nl/afspraak/overzicht/mu2edbyv3bfayubtm/")
+            .unwrap();
+        assert_eq!(url.code(), "mu2edbyv3bfayubtm");
+        let url =
+            EventUrl::parse("https://datumprikker.nl/afspraak/selecteer/f4wfumjp7a9ih2nq").unwrap();
+        assert_eq!(url.code(), "f4wfumjp7a9ih2nq");
+
+        assert_eq!(
+            EventUrl::parse("https://example.com/pbxzxuf7c8sih2nq"),
+            Err(ParseEventUrlError::UnknownHost)
+        );
+        assert_eq!(
+            EventUrl::parse("https://datumprikker.nl/afspraak/bewerk/f4wfumjp7a9ih2nq"),
+            Err(ParseEventUrlError::NotAnEvent)
+        );
+        assert!(matches!(
+            EventUrl::parse("not a url"),
+            Err(ParseEventUrlError::InvalidUrl(_))
+        ));
+
+        let base = url::Url::parse("http://localhost:8080/").unwrap();
+        let url =
+            EventUrl::parse_at(&base, "http://localhost:8080/afspraak/overzicht/abc").unwrap();
+        assert_eq!(url.code(), "abc");
+
+        let mirror = url::Url::parse("http://localhost:8080/dp/afspraak/overzicht/abc").unwrap();
+        let url = EventUrl::try_from(mirror).unwrap();
+        assert_eq!(url.code(), "abc");
+        let other = url::Url::parse("http://localhost:8080/dp/afspraak/bewerk/abc").unwrap();
+        assert_eq!(
+            EventUrl::try_from(other),
+            Err(ParseEventUrlError::NotAnEvent)
+        );
+    }
+
+    #[test]
+    fn site_pages_are_not_events() {
+        for url in [
+            "https://datumprikker.nl/afspraak",
+            "https://datumprikker.nl/contact",
+            "https://datumprikker.nl/login",
+            "https://datumprikker.nl/afspraken",
+            "https://datumprikker.nl/PBXZXUF7C8SIH2NQ",
+        ] {
+            assert_eq!(
+                EventUrl::parse(url),
+                Err(ParseEventUrlError::NotAnEvent),
+                "{}",
+                url
+            );
+        }
+        let other = url::Url::parse("https://evil.example/login").unwrap();
+        assert_eq!(
+            EventUrl::try_from(other),
+            Err(ParseEventUrlError::NotAnEvent)
+        );
+    }
+}
@@ -0,0 +1,168 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use crate::download::{parse_download_url, DownloadError};
+use crate::event::{AdminEvent, Event};
+
+/// Synchronous HTTP transport, so that the pages can be downloaded with any HTTP library
+pub trait HttpFetch {
+    /// Downloads the page at the given URL and returns its body
+    fn fetch(&self, url: &str) -> Result<String, DownloadError>;
+}
+
+pub fn download_event_with(
+    fetch: &impl HttpFetch,
+    url: impl AsRef<str>,
+) -> Result<Event, DownloadError> {
+    let url = parse_download_url(url.as_ref())?;
+    let resp = fetch.fetch(url.as_str())?;
+    let event = crate::event_overview_page::parse_page(resp.as_str())
+        .map_err(DownloadError::parse(&url))?;
+    Ok(event)
+}
+
+/// Downloads the event using the organizer's link, which shows the invitees as well
+pub fn download_event_admin_with(
+    fetch: &impl HttpFetch,
+    url: impl AsRef<str>,
+) -> Result<AdminEvent, DownloadError> {
+    let url = parse_download_url(url.as_ref())?;
+    let resp = fetch.fetch(url.as_str())?;
+    let event = crate::admin_page::parse_page(resp.as_str()).map_err(DownloadError::parse(&url))?;
+    Ok(event)
+}
+
+/// Asynchronous HTTP transport, e.g. the fetch API of a browser when built for WebAssembly
+pub trait AsyncHttpFetch {
+    /// Downloads the page at the given URL and returns its body
+    fn fetch(&self, url: &str) -> impl std::future::Future<Output = Result<String, DownloadError>>;
+}
+
+pub async fn download_event_with_async(
+    fetch: &impl AsyncHttpFetch,
+    url: impl AsRef<str>,
+) -> Result<Event, DownloadError> {
+    let url = parse_download_url(url.as_ref())?;
+    let resp = fetch.fetch(url.as_str()).await?;
+    let event = crate::event_overview_page::parse_page(resp.as_str())
+        .map_err(DownloadError::parse(&url))?;
+    Ok(event)
+}
+
+/// Downloads the event using the organizer's link, which shows the invitees as well
+pub async fn download_event_admin_with_async(
+    fetch: &impl AsyncHttpFetch,
+    url: impl AsRef<str>,
+) -> Result<AdminEvent, DownloadError> {
+    let url = parse_download_url(url.as_ref())?;
+    let resp = fetch.fetch(url.as_str()).await?;
+    let event = crate::admin_page::parse_page(resp.as_str()).map_err(DownloadError::parse(&url))?;
+    Ok(event)
+}
+
+#[cfg(feature = "download")]
+impl AsyncHttpFetch for crate::client::Client {
+    async fn fetch(&self, url: &str) -> Result<String, DownloadError> {
+        Ok(self.fetch_page(url).await?.html)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl HttpFetch for crate::blocking::Client {
+    fn fetch(&self, url: &str) -> Result<String, DownloadError> {
+        self.download_page(url)
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl HttpFetch for ureq::Agent {
+    fn fetch(&self, url: &str) -> Result<String, DownloadError> {
+        match self.get(url).call() {
+            Ok(resp) => resp
+                .into_string()
+                .map_err(|err| DownloadError::TransportError(Box::new(err))),
+            Err(ureq::Error::Status(status, resp)) => Err(crate::download::status_error(
+                &parse_download_url(resp.get_url())?,
+                status,
+                resp.header("Retry-After"),
+            )),
+            Err(err) => Err(DownloadError::TransportError(Box::new(err))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::download::DownloadError;
+    use crate::fetch::{download_event_with, download_event_with_async, AsyncHttpFetch, HttpFetch};
+
+    struct Snapshot;
+
+    impl HttpFetch for Snapshot {
+        fn fetch(&self, _url: &str) -> Result<String, DownloadError> {
+            Ok(include_str!("../data/afspraak_overzicht_in_progress.html").to_string())
+        }
+    }
+
+    impl AsyncHttpFetch for Snapshot {
+        async fn fetch(&self, _url: &str) -> Result<String, DownloadError> {
+            Ok(include_str!("../data/afspraak_overzicht_in_progress.html").to_string())
+        }
+    }
+
+    #[test]
+    fn custom_backend() {
+        let event = download_event_with(
+            &Snapshot,
+            "https://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25",
+        )
+        .unwrap();
+        assert_eq!(event.title, "D&D Avernus week 29");
+    }
+
+    #[tokio::test]
+    async fn custom_async_backend() {
+        let event = download_event_with_async(
+            &Snapshot,
+            "https://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25",
+        )
+        .await
+        .unwrap();
+        assert_eq!(event.title, "D&D Avernus week 29");
+    }
+
+    struct Maintenance;
+
+    impl HttpFetch for Maintenance {
+        fn fetch(&self, _url: &str) -> Result<String, DownloadError> {
+            Ok(String::from(
+                "<html><head><title>Datumprikker is in onderhoud</title></head></html>",
+            ))
+        }
+    }
+
+    #[test]
+    fn maintenance_page() {
+        let result = download_event_with(
+            &Maintenance,
+            "https://datumprikker.nl/afspraak/overzicht/fewqvuycnmvgnx25",
+        );
+        assert!(matches!(
+            result,
+            Err(DownloadError::ServiceUnavailable { .. })
+        ));
+    }
+}
@@ -15,9 +15,109 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
+mod admin_page;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "download")]
+mod cache;
+#[cfg(feature = "download")]
+mod client;
+#[cfg(feature = "cookies")]
+mod cookies;
+mod diff;
+mod display;
 mod download;
 mod event;
 mod event_overview_page;
+mod event_url;
+mod fetch;
+mod load;
+#[cfg(feature = "download")]
+mod middleware;
+#[cfg(feature = "download")]
+mod observer;
+#[cfg(feature = "download")]
+mod rate_limit;
+mod raw_page;
+#[cfg(feature = "download")]
+mod retry;
+#[cfg(all(test, feature = "download"))]
+mod test_server;
+mod windows_timezone;
 
+pub use admin_page::parse_page as parse_admin_page;
+#[cfg(feature = "download")]
+pub use cache::CacheStore;
+#[cfg(feature = "download")]
+pub use cache::CachedPage;
+#[cfg(feature = "download")]
+pub use cache::DiskCache;
+#[cfg(feature = "download")]
+pub use cache::MemoryCache;
+#[cfg(feature = "download")]
+pub use client::Client;
+#[cfg(feature = "download")]
+pub use client::ClientBuilder;
+#[cfg(feature = "cookies")]
+pub use cookies::FileCookieJar;
+pub use diff::EventChange;
+pub use diff::EventDiff;
+pub use display::DateRangeDisplay;
+pub use display::EventDisplay;
+#[cfg(feature = "download")]
 pub use download::download_event;
+#[cfg(feature = "download")]
+pub use download::download_event_admin;
+#[cfg(feature = "download")]
+pub use download::download_event_by_code;
+#[cfg(feature = "download")]
+pub use download::download_events;
+#[cfg(feature = "download")]
+pub use download::export_participants;
+#[cfg(feature = "download")]
+pub use download::fetch_page;
+pub use download::DownloadError;
+pub use event::event_url;
+pub use event::event_url_at;
+pub use event::normalize_event_url;
+pub use event::normalize_event_url_at;
+pub use event::parse_event_code;
+pub use event::AdminEvent;
+pub use event::Availability;
+pub use event::AvailabilityMatrix;
+pub use event::Contact;
+pub use event::DateRange;
 pub use event::Event;
+pub use event::EventBuilder;
+pub use event::EventStatus;
+pub use event::Invitee;
+pub use event::Language;
+pub use event::Organizer;
+pub use event::Participant;
+pub use event::Slot;
+pub use event::Vote;
+pub use event_overview_page::parse_page as parse_event_page;
+pub use event_overview_page::parse_page_lenient as parse_event_page_lenient;
+pub use event_overview_page::FieldError;
+pub use event_overview_page::ParsePageError;
+pub use event_url::EventUrl;
+pub use event_url::ParseEventUrlError;
+pub use fetch::download_event_admin_with;
+pub use fetch::download_event_admin_with_async;
+pub use fetch::download_event_with;
+pub use fetch::download_event_with_async;
+pub use fetch::AsyncHttpFetch;
+pub use fetch::HttpFetch;
+pub use load::load_event_from_path;
+pub use load::parse_event;
+pub use load::LoadError;
+#[cfg(feature = "download")]
+pub use middleware::Middleware;
+#[cfg(feature = "download")]
+pub use observer::RequestInfo;
+#[cfg(feature = "download")]
+pub use observer::RequestObserver;
+pub use raw_page::RawPage;
+#[cfg(feature = "download")]
+pub use retry::RetryPolicy;
+pub use url::Url;
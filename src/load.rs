@@ -0,0 +1,68 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use crate::event::Event;
+use crate::event_overview_page::ParsePageError;
+use std::io::Read;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LoadError {
+    #[error("failed to read page")]
+    IoError(#[from] std::io::Error),
+    #[error("parse error of page")]
+    ParseError(#[from] ParsePageError),
+}
+
+/// Parses an overview page that is read from the reader, e.g. an archived snapshot
+pub fn parse_event(mut reader: impl Read) -> Result<Event, LoadError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    Ok(crate::event_overview_page::parse_page(text.as_str())?)
+}
+
+pub fn load_event_from_path(path: impl AsRef<Path>) -> Result<Event, LoadError> {
+    parse_event(std::fs::File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::load::{load_event_from_path, parse_event, LoadError};
+
+    #[test]
+    fn load_from_path() {
+        let event = load_event_from_path(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/data/afspraak_overzicht_finalized.html"
+        ))
+        .unwrap();
+        assert_eq!(event.title, "D&D Avernus Week 22");
+    }
+
+    #[test]
+    fn parse_from_reader() {
+        let text = include_str!("../data/afspraak_overzicht_participant.html");
+        let event = parse_event(text.as_bytes()).unwrap();
+        assert_eq!(event.title, "test");
+    }
+
+    #[test]
+    fn missing_file() {
+        let event = load_event_from_path("data/does_not_exist.html");
+        assert!(matches!(event, Err(LoadError::IoError(_))));
+    }
+}
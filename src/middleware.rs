@@ -0,0 +1,108 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+/// Hook into the requests of the client, e.g. to add headers, log responses or cache pages
+///
+/// Middleware is called in the order it was added to the client, for every attempt.
+pub trait Middleware: std::fmt::Debug + Send + Sync {
+    /// Answers a page download without sending a request, e.g. from a custom cache
+    ///
+    /// The first middleware that returns a body wins; the other hooks are skipped for that page.
+    fn intercept(&self, _url: &str) -> Option<String> {
+        None
+    }
+
+    /// Adjusts the request before it is sent
+    fn on_request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        request
+    }
+
+    /// Inspects the response before its body is read
+    fn on_response(&self, _response: &reqwest::Response) {}
+
+    /// Inspects or replaces the body of a downloaded page, e.g. to store it in a custom cache
+    fn on_body(&self, _url: &str, body: String) -> String {
+        body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::Client;
+    use crate::middleware::Middleware;
+    use crate::test_server::{response, serve};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Keeps every page in memory and never downloads it again
+    #[derive(Debug, Default)]
+    struct ForeverCache {
+        pages: Mutex<HashMap<String, String>>,
+    }
+
+    impl Middleware for ForeverCache {
+        fn intercept(&self, url: &str) -> Option<String> {
+            self.pages.lock().unwrap().get(url).cloned()
+        }
+
+        fn on_body(&self, url: &str, body: String) -> String {
+            self.pages
+                .lock()
+                .unwrap()
+                .insert(url.to_string(), body.clone());
+            body
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_cache() {
+        let page = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let (base, server) = serve(vec![response("200 OK", &[], page)]);
+        let client = Client::builder()
+            .middleware(ForeverCache::default())
+            .build()
+            .unwrap();
+        let url = base.join("afspraak/overzicht/fewqvuycnmvgnx25").unwrap();
+        let first = client.download_event(&url).await.unwrap();
+        assert_eq!(server.join().unwrap().len(), 1);
+        // The server is gone, so only the middleware can answer
+        let second = client.download_event(&url).await.unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[derive(Debug)]
+    struct ReplaceBody(&'static str);
+
+    impl Middleware for ReplaceBody {
+        fn on_body(&self, _url: &str, _body: String) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn replace_body() {
+        let page = include_str!("../data/afspraak_overzicht_in_progress.html");
+        let (base, server) = serve(vec![response("200 OK", &[], "<html></html>")]);
+        let client = Client::builder()
+            .middleware(ReplaceBody(page))
+            .build()
+            .unwrap();
+        let url = base.join("afspraak/overzicht/fewqvuycnmvgnx25").unwrap();
+        let event = client.download_event(url).await.unwrap();
+        assert_eq!(event.title, "D&D Avernus week 29");
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,47 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use std::time::Duration;
+
+/// Details of a finished request, e.g. for feeding metrics
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RequestInfo {
+    pub url: String,
+    pub duration: Duration,
+    /// HTTP status of the response, or `None` if no response was received
+    pub status: Option<u16>,
+    /// Size of the received body
+    pub bytes: usize,
+    /// The page was not modified, so the cached copy was used
+    pub cache_hit: bool,
+}
+
+impl RequestInfo {
+    pub(crate) fn new(url: &str) -> Self {
+        RequestInfo {
+            url: url.to_string(),
+            duration: Duration::ZERO,
+            status: None,
+            bytes: 0,
+            cache_hit: false,
+        }
+    }
+}
+
+/// Gets notified of every request the client makes, including retries
+pub trait RequestObserver: std::fmt::Debug + Send + Sync {
+    fn on_request(&self, info: &RequestInfo);
+}
@@ -0,0 +1,73 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use crate::event::{AdminEvent, Event};
+use crate::event_overview_page::ParsePageError;
+use chrono::DateTime;
+use chrono::Utc;
+
+/// Page as it was downloaded, so it can be archived next to the parsed event
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawPage {
+    pub html: String,
+    pub fetched_at: DateTime<Utc>,
+    /// URL of the page after following redirects
+    pub final_url: url::Url,
+    /// Response headers as name and value, in the order they were received
+    pub headers: Vec<(String, String)>,
+}
+
+impl RawPage {
+    pub fn parse(&self) -> Result<Event, ParsePageError> {
+        crate::event_overview_page::parse_page(self.html.as_str())
+    }
+
+    pub fn parse_admin(&self) -> Result<AdminEvent, ParsePageError> {
+        crate::admin_page::parse_page(self.html.as_str())
+    }
+
+    /// Value of the first response header with the given name, which is case insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::raw_page::RawPage;
+    use chrono::Utc;
+
+    #[test]
+    fn parse() {
+        let page = RawPage {
+            html: include_str!("../data/afspraak_overzicht_finalized.html").to_string(),
+            fetched_at: Utc::now(),
+            final_url: url::Url::parse(
+                "https://datumprikker.nl/afspraak/overzicht/f4wfumjp7a9ih2nq",
+            )
+            .unwrap(),
+            headers: vec![("etag".to_string(), "\"abc\"".to_string())],
+        };
+        assert_eq!(page.header("ETag"), Some("\"abc\""));
+        assert_eq!(page.header("Last-Modified"), None);
+        assert_eq!(page.parse().unwrap().title, "D&D Avernus Week 22");
+        assert_eq!(page.parse_admin().unwrap().invitees.len(), 4);
+    }
+}
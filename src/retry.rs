@@ -0,0 +1,119 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+use crate::event_overview_page::ParsePageError;
+use std::time::Duration;
+
+/// Controls how often a download is attempted when the network fails
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry, which doubles for every following retry
+    pub base_delay: Duration,
+    /// Maximum random delay added to every retry, so that clients don't retry in lockstep
+    pub jitter: Duration,
+    /// Longest wait that is accepted when the site asks to retry later; longer waits are
+    /// reported as [`crate::DownloadError::RateLimited`]
+    pub max_rate_limit_wait: Duration,
+    /// Parse errors that cause the page to be downloaded again, e.g. for a page that was only
+    /// partially rendered; empty by default
+    pub retry_parse_errors: Vec<ParsePageError>,
+}
+
+impl RetryPolicy {
+    /// Policy that makes a single attempt
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before the given retry, counting from 1
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)));
+        let jitter = if self.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            rand::Rng::gen_range(&mut rand::thread_rng(), Duration::ZERO..=self.jitter)
+        };
+        backoff.saturating_add(jitter)
+    }
+
+    /// Network errors are retried when they are likely transient: a timeout, a failed connection
+    /// or a connection that broke while sending the request or reading the body
+    pub(crate) fn should_retry(&self, attempt: u32, error: &reqwest::Error) -> bool {
+        attempt < self.max_attempts
+            && (error.is_timeout() || error.is_connect() || error.is_request() || error.is_body())
+    }
+
+    pub(crate) fn should_retry_parse(&self, attempt: u32, error: &ParsePageError) -> bool {
+        attempt < self.max_attempts && self.retry_parse_errors.contains(error)
+    }
+
+    pub(crate) fn should_wait_for_rate_limit(&self, attempt: u32, retry_after: Duration) -> bool {
+        attempt < self.max_attempts && retry_after <= self.max_rate_limit_wait
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            jitter: Duration::from_millis(250),
+            max_rate_limit_wait: Duration::from_secs(60),
+            retry_parse_errors: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::retry::RetryPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn exponential_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(100),
+            jitter: Duration::ZERO,
+            ..Default::default()
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn jitter_is_bounded() {
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(100),
+            jitter: Duration::from_millis(50),
+            ..Default::default()
+        };
+        for _ in 0..100 {
+            let delay = policy.delay(1);
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(150));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+/* Copyright (C) 2022 Casper Meijn <casper@meijn.net>
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use chrono_tz::Tz;
+
+/// Converts a Windows time zone name, as used by the site, to an IANA time zone
+///
+/// Uses the primary zone of each Windows name from the CLDR mapping; IANA names are accepted as-is.
+pub fn parse_windows_timezone(name: &str) -> Option<Tz> {
+    let timezone = match name {
+        "Dateline Standard Time" => Tz::Etc__GMTPlus12,
+        "UTC-11" => Tz::Etc__GMTPlus11,
+        "Hawaiian Standard Time" => Tz::Pacific__Honolulu,
+        "Alaskan Standard Time" => Tz::America__Anchorage,
+        "Pacific Standard Time" => Tz::America__Los_Angeles,
+        "US Mountain Standard Time" => Tz::America__Phoenix,
+        "Mountain Standard Time" => Tz::America__Denver,
+        "Central Standard Time" => Tz::America__Chicago,
+        "SA Pacific Standard Time" => Tz::America__Bogota,
+        "Eastern Standard Time" => Tz::America__New_York,
+        "Atlantic Standard Time" => Tz::America__Halifax,
+        "Newfoundland Standard Time" => Tz::America__St_Johns,
+        "E. South America Standard Time" => Tz::America__Sao_Paulo,
+        "Argentina Standard Time" => Tz::America__Argentina__Buenos_Aires,
+        "UTC-02" => Tz::Etc__GMTPlus2,
+        "Azores Standard Time" => Tz::Atlantic__Azores,
+        "Cape Verde Standard Time" => Tz::Atlantic__Cape_Verde,
+        "UTC" => Tz::Etc__UTC,
+        "GMT Standard Time" => Tz::Europe__London,
+        "Greenwich Standard Time" => Tz::Atlantic__Reykjavik,
+        "Morocco Standard Time" => Tz::Africa__Casablanca,
+        "W. Europe Standard Time" => Tz::Europe__Berlin,
+        "Central Europe Standard Time" => Tz::Europe__Budapest,
+        "Romance Standard Time" => Tz::Europe__Paris,
+        "Central European Standard Time" => Tz::Europe__Warsaw,
+        "W. Central Africa Standard Time" => Tz::Africa__Lagos,
+        "GTB Standard Time" => Tz::Europe__Bucharest,
+        "E. Europe Standard Time" => Tz::Europe__Chisinau,
+        "FLE Standard Time" => Tz::Europe__Kiev,
+        "Egypt Standard Time" => Tz::Africa__Cairo,
+        "South Africa Standard Time" => Tz::Africa__Johannesburg,
+        "Israel Standard Time" => Tz::Asia__Jerusalem,
+        "Turkey Standard Time" => Tz::Europe__Istanbul,
+        "Russian Standard Time" => Tz::Europe__Moscow,
+        "Arabian Standard Time" => Tz::Asia__Dubai,
+        "India Standard Time" => Tz::Asia__Kolkata,
+        "SE Asia Standard Time" => Tz::Asia__Bangkok,
+        "China Standard Time" => Tz::Asia__Shanghai,
+        "Singapore Standard Time" => Tz::Asia__Singapore,
+        "W. Australia Standard Time" => Tz::Australia__Perth,
+        "Tokyo Standard Time" => Tz::Asia__Tokyo,
+        "Korea Standard Time" => Tz::Asia__Seoul,
+        "AUS Eastern Standard Time" => Tz::Australia__Sydney,
+        "New Zealand Standard Time" => Tz::Pacific__Auckland,
+        _ => return name.parse().ok(),
+    };
+    Some(timezone)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::windows_timezone::parse_windows_timezone;
+
+    #[test]
+    fn windows_names() {
+        assert_eq!(
+            parse_windows_timezone("W. Europe Standard Time"),
+            Some(chrono_tz::Europe::Berlin)
+        );
+        assert_eq!(
+            parse_windows_timezone("GMT Standard Time"),
+            Some(chrono_tz::Europe::London)
+        );
+    }
+
+    #[test]
+    fn iana_names() {
+        assert_eq!(
+            parse_windows_timezone("Europe/Amsterdam"),
+            Some(chrono_tz::Europe::Amsterdam)
+        );
+        assert_eq!(parse_windows_timezone("Middle Earth Standard Time"), None);
+    }
+}